@@ -0,0 +1,268 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! CPU side pixel storage: an owned `Bitmap` and a borrowed `Pixmap` view.
+
+use std::marker::PhantomData;
+use std::ptr;
+
+use euclid;
+use sys;
+
+use super::{ImageInfo, IRect, Color, Image, Error, BasicSurface, Surface,
+            to_ffi_irect};
+
+/// A mutable view of pixels described by an `ImageInfo`.
+pub struct Pixmap<'a> {
+    info: ImageInfo,
+    pixels: &'a mut [u8],
+    row_bytes: usize,
+}
+
+impl<'a> Pixmap<'a> {
+    /// Fails if `info` is empty, or `pixels` is too small for it with a row
+    /// stride of `row_bytes`.
+    pub fn new(info: ImageInfo, pixels: &'a mut [u8], row_bytes: usize) -> Result<Pixmap<'a>, Error> {
+        info.check_pixels(pixels.len(), row_bytes)?;
+        Ok(Pixmap {
+            info: info,
+            pixels: pixels,
+            row_bytes: row_bytes,
+        })
+    }
+
+    pub fn info(&self) -> &ImageInfo { &self.info }
+    pub fn row_bytes(&self) -> usize { self.row_bytes }
+    pub fn pixels(&self) -> &[u8] { &self.pixels[..] }
+    pub fn pixels_mut(&mut self) -> &mut [u8] { &mut self.pixels[..] }
+
+    /// The pixels are borrowed, so the returned struct must not outlive
+    /// `self`. Only for calls which read the pixels; see `to_ffi_mut`.
+    fn to_ffi(&self) -> sys::Pixmap {
+        ffi_pixmap(&self.info, self.pixels.as_ptr() as *mut u8, self.row_bytes)
+    }
+    /// For calls which write the pixels.
+    fn to_ffi_mut(&mut self) -> sys::Pixmap {
+        ffi_pixmap(&self.info, self.pixels.as_mut_ptr(), self.row_bytes)
+    }
+
+    /// The color of the pixel at `x`, `y`, unpremultiplied and converted to
+    /// 8 bits. Returns `None` if it is out of bounds.
+    pub fn get_color(&self, x: i32, y: i32) -> Option<Color> {
+        get_color(self.to_ffi(), x, y)
+    }
+    /// Fill `area`, or all pixels if `None`, with `color`.
+    pub fn erase(&mut self, color: Color, area: Option<IRect>) -> Result<(), Error> {
+        let area = area.map(|a| to_ffi_irect(a) );
+        let area_ptr = area.as_ref()
+            .map(|a| a as *const sys::IRect )
+            .unwrap_or(ptr::null());
+        let ok = unsafe {
+            sys::sk_pixmap_erase(self.to_ffi_mut(), color.into(), area_ptr)
+        };
+        if ok { Ok(()) } else { Err(Error::ColorType) }
+    }
+    /// A view of the pixels within `subset`. Returns `None` if `subset` isn't
+    /// within bounds or is empty.
+    pub fn extract_subset(&mut self, subset: IRect) -> Option<Pixmap> {
+        subset_range(&self.info, self.row_bytes, subset)
+            .map(move |(info, start, end)| Pixmap {
+                info: info,
+                pixels: &mut self.pixels[start..end],
+                row_bytes: self.row_bytes,
+            })
+    }
+
+    /// Copy the pixels into a new image.
+    pub fn to_image(&self) -> Option<Image> {
+        to_image(self.to_ffi())
+    }
+    /// Draw directly into the pixels.
+    pub fn canvas(&mut self) -> Result<PixelsSurface, Error> {
+        unsafe {
+            PixelsSurface::new(&self.info, self.pixels.as_mut_ptr(), self.row_bytes)
+        }
+    }
+}
+
+/// Shared by `Pixmap` and `Bitmap`. The pixels at `addr` are borrowed, so the
+/// returned struct must not outlive them, and Skia may only write through it
+/// if `addr` came from a mutable borrow.
+fn ffi_pixmap(info: &ImageInfo, addr: *mut u8, row_bytes: usize) -> sys::Pixmap {
+    sys::Pixmap {
+        info: info.into(),
+        color_space: info.ffi_color_space(),
+        addr: addr as *mut _,
+        row_bytes: row_bytes,
+    }
+}
+fn get_color(pixmap: sys::Pixmap, x: i32, y: i32) -> Option<Color> {
+    if x < 0 || y < 0 || x >= pixmap.info.width || y >= pixmap.info.height {
+        return None;
+    }
+    Some(From::from(unsafe {
+        sys::sk_pixmap_get_color(pixmap, x, y)
+    }))
+}
+fn to_image(pixmap: sys::Pixmap) -> Option<Image> {
+    let image = unsafe {
+        sys::sk_new_image_raster_copy(pixmap)
+    };
+    if image == ptr::null_mut() {
+        None
+    } else {
+        Some(Image(image))
+    }
+}
+
+/// Returns the info of `subset`, and the byte range it covers.
+fn subset_range(info: &ImageInfo, row_bytes: usize,
+                subset: IRect) -> Option<(ImageInfo, usize, usize)> {
+    if subset.left < 0 || subset.top < 0 || subset.right > info.size.width ||
+        subset.bottom > info.size.height || subset.left >= subset.right ||
+        subset.top >= subset.bottom
+    {
+        return None;
+    }
+
+    let mut sub_info = info.clone();
+    sub_info.size = euclid::Size2D::new(subset.right - subset.left,
+                                        subset.bottom - subset.top);
+    let start = subset.top as usize * row_bytes + subset.left as usize * info.bytes_per_pixel();
+    let end = start + sub_info.compute_byte_size(row_bytes)?;
+    Some((sub_info, start, end))
+}
+
+/// A surface drawing into a `Pixmap`'s or `Bitmap`'s pixels, which remain
+/// borrowed until it is dropped.
+pub struct PixelsSurface<'a> {
+    surface: BasicSurface,
+    _pixels: PhantomData<&'a mut [u8]>,
+}
+impl<'a> PixelsSurface<'a> {
+    /// The caller must borrow the pixels at `addr` mutably for `'a`.
+    unsafe fn new(info: &ImageInfo, addr: *mut u8,
+                  row_bytes: usize) -> Result<PixelsSurface<'a>, Error> {
+        let surface = sys::sk_new_raster_direct_surface_with_color_space(info.into(),
+                                                                         info.ffi_color_space(),
+                                                                         addr as *mut _,
+                                                                         row_bytes);
+        if surface == ptr::null_mut() {
+            Err(Error::ColorType)
+        } else {
+            Ok(PixelsSurface {
+                surface: BasicSurface(surface),
+                _pixels: PhantomData,
+            })
+        }
+    }
+}
+impl<'a> Surface for PixelsSurface<'a> {
+    fn basic_surface(&self) -> &BasicSurface { &self.surface }
+}
+
+/// Owned pixels described by an `ImageInfo`.
+pub struct Bitmap {
+    info: ImageInfo,
+    pixels: Vec<u8>,
+    row_bytes: usize,
+    immutable: bool,
+}
+
+impl Bitmap {
+    /// Allocate zeroed pixels, with rows tightly packed. Fails with
+    /// `Error::Pixels` if the info is empty or too large, like `Pixmap::new`.
+    pub fn new(info: ImageInfo) -> Result<Bitmap, Error> {
+        if info.bytes_per_pixel() == 0 {
+            return Err(Error::ColorType);
+        }
+        if info.size.width <= 0 || info.size.height <= 0 {
+            return Err(Error::Pixels);
+        }
+        let row_bytes = info.min_row_bytes().ok_or(Error::Pixels)?;
+        let byte_size = info.compute_byte_size(row_bytes).ok_or(Error::Pixels)?;
+        let pixels = vec![0u8; byte_size];
+        Ok(Bitmap {
+            info: info,
+            pixels: pixels,
+            row_bytes: row_bytes,
+            immutable: false,
+        })
+    }
+
+    pub fn info(&self) -> &ImageInfo { &self.info }
+    pub fn row_bytes(&self) -> usize { self.row_bytes }
+    pub fn pixels(&self) -> &[u8] { &self.pixels[..] }
+    /// Returns `None` if the bitmap is immutable.
+    pub fn pixels_mut(&mut self) -> Option<&mut [u8]> {
+        if self.immutable { None } else { Some(&mut self.pixels[..]) }
+    }
+
+    /// Prevent any further changes to the pixels. This can't be undone.
+    pub fn set_immutable(&mut self) {
+        self.immutable = true;
+    }
+    pub fn is_immutable(&self) -> bool { self.immutable }
+
+    /// See `Pixmap::to_ffi`; writes go through `pixmap()`.
+    fn to_ffi(&self) -> sys::Pixmap {
+        ffi_pixmap(&self.info, self.pixels.as_ptr() as *mut u8, self.row_bytes)
+    }
+
+    /// Returns `None` if the bitmap is immutable.
+    pub fn pixmap(&mut self) -> Option<Pixmap> {
+        if self.immutable {
+            return None;
+        }
+        Some(Pixmap {
+            info: self.info.clone(),
+            pixels: &mut self.pixels[..],
+            row_bytes: self.row_bytes,
+        })
+    }
+
+    pub fn get_color(&self, x: i32, y: i32) -> Option<Color> {
+        get_color(self.to_ffi(), x, y)
+    }
+    /// Fill `area`, or all pixels if `None`, with `color`. Fails if the
+    /// bitmap is immutable.
+    pub fn erase(&mut self, color: Color, area: Option<IRect>) -> Result<(), Error> {
+        match self.pixmap() {
+            Some(mut p) => p.erase(color, area),
+            None => Err(Error::Immutable),
+        }
+    }
+    /// Copy the pixels within `subset` into a new, mutable, bitmap. Returns
+    /// `None` if `subset` isn't within bounds or is empty.
+    pub fn extract_subset(&self, subset: IRect) -> Option<Bitmap> {
+        let (info, start, _) = match subset_range(&self.info, self.row_bytes, subset) {
+            Some(r) => r,
+            None => return None,
+        };
+        let mut sub = match Bitmap::new(info) {
+            Ok(b) => b,
+            Err(_) => return None,
+        };
+        let row_len = sub.row_bytes;
+        for (y, dst) in sub.pixels.chunks_mut(row_len).enumerate() {
+            let src = start + y * self.row_bytes;
+            dst.copy_from_slice(&self.pixels[src..src + row_len]);
+        }
+        Some(sub)
+    }
+
+    /// Copy the pixels into a new image.
+    pub fn to_image(&self) -> Option<Image> {
+        to_image(self.to_ffi())
+    }
+    /// Draw directly into the pixels. Fails if the bitmap is immutable.
+    pub fn canvas(&mut self) -> Result<PixelsSurface, Error> {
+        if self.immutable {
+            return Err(Error::Immutable);
+        }
+        unsafe {
+            PixelsSurface::new(&self.info, self.pixels.as_mut_ptr(), self.row_bytes)
+        }
+    }
+}
@@ -0,0 +1,452 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! CSS color strings and HSV/HSL conversions for `Color`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Color, Error};
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()`,
+/// `hsla()`, `transparent` and the CSS named colors. Both the comma and the
+/// space separated function syntaxes are accepted.
+impl FromStr for Color {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Color, Error> {
+        let s = s.trim().to_lowercase();
+        if let Some(hex) = s.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+        if let Some(open) = s.find('(') {
+            if !s.ends_with(')') {
+                return Err(Error::CssColor);
+            }
+            let args = function_args(&s[open + 1..s.len() - 1])?;
+            return match s[..open].trim() {
+                "rgb" | "rgba" => parse_rgb(&args),
+                "hsl" | "hsla" => parse_hsl(&args),
+                _ => Err(Error::CssColor),
+            };
+        }
+        if s == "transparent" {
+            return Ok(Color::new(0, 0, 0, 0));
+        }
+        NAMED_COLORS.binary_search_by(|&(name, _)| name.cmp(&s[..]) )
+            .map(|idx| {
+                let rgb = NAMED_COLORS[idx].1;
+                Color::new(0xff, (rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+            })
+            .map_err(|_| Error::CssColor )
+    }
+}
+
+/// Writes `#rrggbb`, or `#rrggbbaa` if the color isn't opaque.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r(), self.g(), self.b())?;
+        if self.a() != 0xff {
+            write!(f, "{:02x}", self.a())?;
+        }
+        Ok(())
+    }
+}
+impl fmt::Debug for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Color({})", self)
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Color, Error> {
+    let mut digits = Vec::with_capacity(8);
+    for c in hex.chars() {
+        match c.to_digit(16) {
+            Some(d) => digits.push(d as u8),
+            None => return Err(Error::CssColor),
+        }
+    }
+    let (r, g, b, a) = match digits.len() {
+        3 | 4 => {
+            let a = if digits.len() == 4 { digits[3] * 0x11 } else { 0xff };
+            (digits[0] * 0x11, digits[1] * 0x11, digits[2] * 0x11, a)
+        },
+        6 | 8 => {
+            let byte = |i: usize| digits[i * 2] << 4 | digits[i * 2 + 1];
+            let a = if digits.len() == 8 { byte(3) } else { 0xff };
+            (byte(0), byte(1), byte(2), a)
+        },
+        _ => return Err(Error::CssColor),
+    };
+    Ok(Color::new(a, r, g, b))
+}
+
+/// Splits either `a, b, c[, d]` or `a b c[ / d]`; the two separator styles
+/// can't be mixed.
+fn function_args(args: &str) -> Result<Vec<&str>, Error> {
+    let args: Vec<&str> = if args.contains(',') {
+        args.split(',').map(|a| a.trim() ).collect()
+    } else {
+        let mut parts = args.split('/');
+        let mut args: Vec<&str> = parts.next().unwrap().split_whitespace().collect();
+        if let Some(alpha) = parts.next() {
+            let mut alpha = alpha.split_whitespace();
+            match (alpha.next(), alpha.next()) {
+                (Some(a), None) if args.len() == 3 => args.push(a),
+                _ => return Err(Error::CssColor),
+            }
+        } else if args.len() != 3 {
+            return Err(Error::CssColor);
+        }
+        if parts.next().is_some() {
+            return Err(Error::CssColor);
+        }
+        args
+    };
+    let valid = args.iter()
+        .all(|a| !a.is_empty() && !a.contains(|c: char| c == '/' || c.is_whitespace() ) );
+    if valid && (args.len() == 3 || args.len() == 4) {
+        Ok(args)
+    } else {
+        Err(Error::CssColor)
+    }
+}
+
+/// A finite number; `f32`'s parser also takes `inf` and `nan`.
+fn parse_number(s: &str) -> Result<f32, Error> {
+    match s.parse::<f32>() {
+        Ok(v) if v.is_finite() => Ok(v),
+        _ => Err(Error::CssColor),
+    }
+}
+/// A number, or a percentage of `percent_of`.
+fn parse_component(s: &str, percent_of: f32) -> Result<f32, Error> {
+    if let Some(percent) = s.strip_suffix('%') {
+        Ok(parse_number(percent)? / 100.0 * percent_of)
+    } else {
+        parse_number(s)
+    }
+}
+fn parse_alpha(args: &[&str]) -> Result<u8, Error> {
+    match args.get(3) {
+        Some(a) => Ok(unit_to_u8(parse_component(a, 1.0)?)),
+        None => Ok(0xff),
+    }
+}
+fn unit_to_u8(v: f32) -> u8 {
+    let v = if v > 1.0 { 1.0 } else if v > 0.0 { v } else { 0.0 };
+    (v * 255.0 + 0.5) as u8
+}
+fn byte_to_u8(v: f32) -> u8 {
+    let v = if v > 255.0 { 255.0 } else if v > 0.0 { v } else { 0.0 };
+    (v + 0.5) as u8
+}
+
+fn parse_rgb(args: &[&str]) -> Result<Color, Error> {
+    let r = byte_to_u8(parse_component(args[0], 255.0)?);
+    let g = byte_to_u8(parse_component(args[1], 255.0)?);
+    let b = byte_to_u8(parse_component(args[2], 255.0)?);
+    Ok(Color::new(parse_alpha(args)?, r, g, b))
+}
+fn parse_hsl(args: &[&str]) -> Result<Color, Error> {
+    let h = args[0].trim_end_matches("deg");
+    let h = parse_number(h)?;
+    if !args[1].ends_with('%') || !args[2].ends_with('%') {
+        return Err(Error::CssColor);
+    }
+    let s = parse_component(args[1], 1.0)?;
+    let l = parse_component(args[2], 1.0)?;
+    Ok(Color::from_hsl(parse_alpha(args)?, [h, s, l]))
+}
+
+/// The hue in degrees, and the chroma range, of `c`.
+fn hue_and_range(c: Color) -> (f32, f32, f32) {
+    let (r, g, b) = (c.r() as f32 / 255.0, c.g() as f32 / 255.0, c.b() as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, min, max);
+    }
+    let h = if r == max {
+        (g - b) / delta
+    } else if g == max {
+        2.0 + (b - r) / delta
+    } else {
+        4.0 + (r - g) / delta
+    };
+    let h = h * 60.0;
+    (if h < 0.0 { h + 360.0 } else { h }, min, max)
+}
+/// The rgb of a color with chroma `c`, offset by `m`, at hue `h` degrees.
+fn from_hue(alpha: u8, h: f32, c: f32, m: f32) -> Color {
+    let h = ((h % 360.0) + 360.0) % 360.0 / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Color::new(alpha, unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m))
+}
+fn clamp_unit(v: f32) -> f32 {
+    if v > 1.0 { 1.0 } else if v > 0.0 { v } else { 0.0 }
+}
+
+impl Color {
+    /// Returns hue in degrees `[0, 360)`, and saturation and value in
+    /// `[0, 1]`. Alpha is ignored.
+    pub fn to_hsv(&self) -> [f32; 3] {
+        let (h, min, max) = hue_and_range(*self);
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        [h, s, max]
+    }
+    /// The inverse of `to_hsv`. Saturation and value are clamped to `[0, 1]`.
+    pub fn from_hsv(alpha: u8, hsv: [f32; 3]) -> Color {
+        let s = clamp_unit(hsv[1]);
+        let v = clamp_unit(hsv[2]);
+        let c = v * s;
+        from_hue(alpha, hsv[0], c, v - c)
+    }
+    /// Returns hue in degrees `[0, 360)`, and saturation and lightness in
+    /// `[0, 1]`. Alpha is ignored.
+    pub fn to_hsl(&self) -> [f32; 3] {
+        let (h, min, max) = hue_and_range(*self);
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        let s = if delta == 0.0 { 0.0 } else { delta / (1.0 - (2.0 * l - 1.0).abs()) };
+        [h, s, l]
+    }
+    /// The inverse of `to_hsl`. Saturation and lightness are clamped to
+    /// `[0, 1]`.
+    pub fn from_hsl(alpha: u8, hsl: [f32; 3]) -> Color {
+        let s = clamp_unit(hsl[1]);
+        let l = clamp_unit(hsl[2]);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        from_hue(alpha, hsl[0], c, l - c / 2.0)
+    }
+}
+
+/// Sorted by name, for binary search.
+static NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Color, Error};
+
+    fn parse(s: &str) -> Result<Color, Error> { s.parse() }
+
+    #[test]
+    fn from_str() {
+        let cases = [
+            ("#f00", Color::new(0xff, 0xff, 0, 0)),
+            ("#0f08", Color::new(0x88, 0, 0xff, 0)),
+            ("#123456", Color::new(0xff, 0x12, 0x34, 0x56)),
+            ("#12345678", Color::new(0x78, 0x12, 0x34, 0x56)),
+            ("rgb(255, 0, 0)", Color::new(0xff, 0xff, 0, 0)),
+            ("rgba(0,128,255,0.5)", Color::new(0x80, 0, 128, 255)),
+            ("rgb(1 2 3)", Color::new(0xff, 1, 2, 3)),
+            ("rgb(100% 0% 50% / 50%)", Color::new(0x80, 0xff, 0, 0x80)),
+            ("rgb(1 2 3/0)", Color::new(0, 1, 2, 3)),
+            ("RGB( 300 , -1 , 2.6 )", Color::new(0xff, 0xff, 0, 3)),
+            ("hsl(120, 100%, 50%)", Color::new(0xff, 0, 0xff, 0)),
+            ("hsla(240deg 100% 25% / 1)", Color::new(0xff, 0, 0, 0x80)),
+            ("  RebeccaPurple ", Color::new(0xff, 0x66, 0x33, 0x99)),
+            ("transparent", Color::new(0, 0, 0, 0)),
+        ];
+        for &(s, c) in cases.iter() {
+            assert_eq!(parse(s), Ok(c), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        let cases = [
+            "", "#", "#12", "#12345", "#ggg", "nope", "rgb", "rgb(", "rgb()", "foo(1, 2, 3)",
+            "rgb(1, 2)", "rgb(1, 2, 3, 4, 5)", "rgb(,,,1,2,3)", "rgb(1,,2,3)", "rgb(1, 2, 3,)",
+            "rgb(1 2)", "rgb(1 2 3 4)", "rgb(255 0 0 50%)", "rgb(1, 2 3)", "rgb(1 2, 3)",
+            "rgb(1, 2, 3 / 1)", "rgb(1 2 3 /)", "rgb(1 2 3 / 1 2)", "rgb(1 2 / 3)",
+            "rgb(1 2 3 / 4 / 5)", "rgb(nan, 0, 0)", "rgb(inf 0 0)", "rgb(0, 0, 0, NaN%)",
+            "hsl(nan, 50%, 50%)", "hsl(120, 100, 50%)", "rgb(1, 2, 3",
+        ];
+        for s in cases.iter() {
+            assert_eq!(parse(s), Err(Error::CssColor), "{}", s);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Color::new(0xff, 0x12, 0xab, 0).to_string(), "#12ab00");
+        assert_eq!(Color::new(0x7f, 1, 2, 3).to_string(), "#0102037f");
+        for &c in [Color::new(0xff, 0x12, 0xab, 0), Color::new(0, 0xff, 0xff, 0xff)].iter() {
+            assert_eq!(parse(&c.to_string()), Ok(c));
+        }
+    }
+
+    #[test]
+    fn hsv_hsl_round_trip() {
+        assert_eq!(Color::new(0xff, 0xff, 0, 0).to_hsv(), [0.0, 1.0, 1.0]);
+        assert_eq!(Color::new(0xff, 0, 0xff, 0).to_hsl(), [120.0, 1.0, 0.5]);
+        for r in (0..256).step_by(15) {
+            for g in (0..256).step_by(17) {
+                for b in (0..256).step_by(51) {
+                    let c = Color::new(0x40, r as u8, g as u8, b as u8);
+                    assert_eq!(Color::from_hsv(c.a(), c.to_hsv()), c);
+                    assert_eq!(Color::from_hsl(c.a(), c.to_hsl()), c);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Text rendering settings, independent of `Paint`.
+
+use std::ptr;
+
+use sys;
+
+use libc;
+
+use super::{Typeface, Paint, Path, FRect, FPoint, Matrix, AddPathMode, from_ffi_frect,
+            from_ffi_point, from_ffi_matrix, to_ffi_point};
+
+pub use sys::{FontEdging, FontHinting};
+
+pub type GlyphId = u16;
+
+/// Text in one of the encodings Skia understands. Glyph IDs are only
+/// meaningful for the typeface they came from.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EncodedText<'a> {
+    Utf8(&'a str),
+    Utf16(&'a [u16]),
+    Utf32(&'a [char]),
+    Glyphs(&'a [GlyphId]),
+}
+impl<'a> From<&'a str> for EncodedText<'a> {
+    fn from(s: &'a str) -> EncodedText<'a> {
+        EncodedText::Utf8(s)
+    }
+}
+impl<'a> From<&'a String> for EncodedText<'a> {
+    fn from(s: &'a String) -> EncodedText<'a> {
+        EncodedText::Utf8(&s[..])
+    }
+}
+impl<'a> EncodedText<'a> {
+    /// The text pointer, its length in bytes, and its encoding.
+    pub(crate) fn to_ffi(&self) -> (*const libc::c_void, libc::size_t, sys::TextEncoding) {
+        use std::mem::size_of;
+        match *self {
+            EncodedText::Utf8(s) => (s.as_ptr() as *const _, s.len(), sys::TextEncoding::Utf8),
+            EncodedText::Utf16(s) => (s.as_ptr() as *const _, s.len() * size_of::<u16>(),
+                                      sys::TextEncoding::Utf16),
+            EncodedText::Utf32(s) => (s.as_ptr() as *const _, s.len() * size_of::<char>(),
+                                      sys::TextEncoding::Utf32),
+            EncodedText::Glyphs(s) => (s.as_ptr() as *const _, s.len() * size_of::<GlyphId>(),
+                                       sys::TextEncoding::GlyphId),
+        }
+    }
+}
+
+/// Aka `SkFontMetrics`. Distances are relative to the baseline, with y down,
+/// so eg `ascent` is usually negative. Metrics which fonts may not provide are
+/// `None` when they don't.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct FontMetrics {
+    /// The greatest extent above the baseline of any glyph.
+    pub top: f32,
+    /// The recommended distance above the baseline.
+    pub ascent: f32,
+    /// The recommended distance below the baseline.
+    pub descent: f32,
+    /// The greatest extent below the baseline of any glyph.
+    pub bottom: f32,
+    /// The recommended gap between lines.
+    pub leading: f32,
+    pub avg_char_width: f32,
+    pub max_char_width: f32,
+    /// The minimum and maximum x of any glyph's bounds; `None` if the font
+    /// doesn't provide them, eg for variable fonts.
+    pub x_min: Option<f32>,
+    pub x_max: Option<f32>,
+    /// The height of a lowercase 'x', or 0 if unknown.
+    pub x_height: f32,
+    /// The height of an uppercase letter, or 0 if unknown.
+    pub cap_height: f32,
+    pub underline_thickness: Option<f32>,
+    pub underline_position: Option<f32>,
+    pub strikeout_thickness: Option<f32>,
+    pub strikeout_position: Option<f32>,
+}
+impl FontMetrics {
+    /// The recommended distance between consecutive baselines.
+    pub fn line_spacing(&self) -> f32 {
+        self.descent - self.ascent + self.leading
+    }
+}
+impl From<sys::FontMetrics> for FontMetrics {
+    fn from(m: sys::FontMetrics) -> FontMetrics {
+        let valid = |flag: sys::FontMetricsFlags, v: f32| {
+            if m.flags & flag as u32 != 0 { Some(v) } else { None }
+        };
+        let bounds_valid = m.flags & sys::FontMetricsFlags::BoundsInvalid as u32 == 0;
+        FontMetrics {
+            top: m.top,
+            ascent: m.ascent,
+            descent: m.descent,
+            bottom: m.bottom,
+            leading: m.leading,
+            avg_char_width: m.avg_char_width,
+            max_char_width: m.max_char_width,
+            x_min: if bounds_valid { Some(m.x_min) } else { None },
+            x_max: if bounds_valid { Some(m.x_max) } else { None },
+            x_height: m.x_height,
+            cap_height: m.cap_height,
+            underline_thickness: valid(sys::FontMetricsFlags::UnderlineThicknessIsValid,
+                                       m.underline_thickness),
+            underline_position: valid(sys::FontMetricsFlags::UnderlinePositionIsValid,
+                                      m.underline_position),
+            strikeout_thickness: valid(sys::FontMetricsFlags::StrikeoutThicknessIsValid,
+                                       m.strikeout_thickness),
+            strikeout_position: valid(sys::FontMetricsFlags::StrikeoutPositionIsValid,
+                                      m.strikeout_position),
+        }
+    }
+}
+
+/// Aka `SkFont`. The typeface, size and rendering settings for text; colors
+/// and effects still come from the `Paint` it is drawn with.
+pub struct Font(pub(crate) sys::Font);
+unsafe impl Send for Font { }
+impl Default for Font {
+    /// The default typeface at a size of 12.
+    fn default() -> Font {
+        Font(unsafe {
+            sys::sk_new_font()
+        })
+    }
+}
+impl Clone for Font {
+    fn clone(&self) -> Font {
+        Font(unsafe {
+            sys::sk_new_font_copy(self.0)
+        })
+    }
+}
+impl Drop for Font {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_del_font(self.0)
+        };
+    }
+}
+
+impl Font {
+    pub fn new(tf: Option<&Typeface>, size: f32) -> Font {
+        let mut font: Font = Default::default();
+        font.set_typeface(tf);
+        font.set_size(size);
+        font
+    }
+
+    pub fn get_typeface(&self) -> Option<Typeface> {
+        let tf = unsafe {
+            sys::sk_font_ref_typeface(self.0)
+        };
+        if tf == ptr::null_mut() {
+            None
+        } else {
+            Some(Typeface(tf))
+        }
+    }
+    /// `None` uses the default typeface.
+    pub fn set_typeface(&mut self, tf: Option<&Typeface>) {
+        let tf_ptr = tf.map(|t| t.0 ).unwrap_or(ptr::null_mut());
+        unsafe {
+            sys::sk_font_set_typeface(self.0, tf_ptr);
+        }
+    }
+    pub fn get_size(&self) -> f32 {
+        unsafe {
+            sys::sk_font_get_size(self.0)
+        }
+    }
+    pub fn set_size(&mut self, size: f32) {
+        unsafe {
+            sys::sk_font_set_size(self.0, size);
+        }
+    }
+    pub fn get_scale_x(&self) -> f32 {
+        unsafe {
+            sys::sk_font_get_scale_x(self.0)
+        }
+    }
+    pub fn set_scale_x(&mut self, s: f32) {
+        unsafe {
+            sys::sk_font_set_scale_x(self.0, s);
+        }
+    }
+    pub fn get_skew_x(&self) -> f32 {
+        unsafe {
+            sys::sk_font_get_skew_x(self.0)
+        }
+    }
+    /// Negative values lean the text to the right, as in fake italics.
+    pub fn set_skew_x(&mut self, s: f32) {
+        unsafe {
+            sys::sk_font_set_skew_x(self.0, s);
+        }
+    }
+    pub fn get_edging(&self) -> FontEdging {
+        unsafe {
+            sys::sk_font_get_edging(self.0)
+        }
+    }
+    pub fn set_edging(&mut self, e: FontEdging) {
+        unsafe {
+            sys::sk_font_set_edging(self.0, e);
+        }
+    }
+    pub fn get_hinting(&self) -> FontHinting {
+        unsafe {
+            sys::sk_font_get_hinting(self.0)
+        }
+    }
+    pub fn set_hinting(&mut self, h: FontHinting) {
+        unsafe {
+            sys::sk_font_set_hinting(self.0, h);
+        }
+    }
+    pub fn get_embolden(&self) -> bool {
+        unsafe {
+            sys::sk_font_get_embolden(self.0)
+        }
+    }
+    /// Fake bold for typefaces without a bold style.
+    pub fn set_embolden(&mut self, v: bool) {
+        unsafe {
+            sys::sk_font_set_embolden(self.0, v);
+        }
+    }
+    pub fn get_baseline_snap(&self) -> bool {
+        unsafe {
+            sys::sk_font_get_baseline_snap(self.0)
+        }
+    }
+    /// Round the baseline to whole pixels.
+    pub fn set_baseline_snap(&mut self, v: bool) {
+        unsafe {
+            sys::sk_font_set_baseline_snap(self.0, v);
+        }
+    }
+    pub fn get_linear_metrics(&self) -> bool {
+        unsafe {
+            sys::sk_font_get_linear_metrics(self.0)
+        }
+    }
+    /// Use unhinted metrics, so text scales linearly with its size.
+    pub fn set_linear_metrics(&mut self, v: bool) {
+        unsafe {
+            sys::sk_font_set_linear_metrics(self.0, v);
+        }
+    }
+    pub fn get_embedded_bitmaps(&self) -> bool {
+        unsafe {
+            sys::sk_font_get_embedded_bitmaps(self.0)
+        }
+    }
+    /// Use bitmap strikes embedded in the font when available.
+    pub fn set_embedded_bitmaps(&mut self, v: bool) {
+        unsafe {
+            sys::sk_font_set_embedded_bitmaps(self.0, v);
+        }
+    }
+
+    /// The metrics of the typeface at this font's size and scale.
+    pub fn metrics(&self) -> FontMetrics {
+        let mut m: sys::FontMetrics = Default::default();
+        unsafe {
+            sys::sk_font_get_metrics(self.0, &mut m as *mut _);
+        }
+        From::from(m)
+    }
+
+    /// Returns the advance width of `text`, and its bounds relative to the
+    /// origin. If `paint` is given its stroke and effects are included in the
+    /// bounds.
+    pub fn measure_text<'a, T>(&self, text: T, paint: Option<&Paint>) -> (f32, FRect)
+        where T: Into<EncodedText<'a>>,
+    {
+        let (ptr, len, encoding) = text.into().to_ffi();
+        let paint_ptr = paint.map(|p| p.0 ).unwrap_or(ptr::null_mut());
+        let mut bounds: sys::Rect = Default::default();
+        let advance = unsafe {
+            sys::sk_font_measure_text(self.0, ptr, len, encoding, &mut bounds as *mut _,
+                                      paint_ptr)
+        };
+        (advance, from_ffi_frect(bounds))
+    }
+
+    /// The number of glyphs `text` maps to.
+    pub fn count_text<'a, T>(&self, text: T) -> usize
+        where T: Into<EncodedText<'a>>,
+    {
+        let (ptr, len, encoding) = text.into().to_ffi();
+        unsafe {
+            sys::sk_font_text_to_glyphs(self.0, ptr, len, encoding, ptr::null_mut(), 0) as usize
+        }
+    }
+    /// Map each character of `text` to a glyph of the typeface. Characters
+    /// the typeface has no glyph for map to 0.
+    pub fn text_to_glyphs<'a, T>(&self, text: T) -> Vec<GlyphId>
+        where T: Into<EncodedText<'a>>,
+    {
+        let (ptr, len, encoding) = text.into().to_ffi();
+        let count = unsafe {
+            sys::sk_font_text_to_glyphs(self.0, ptr, len, encoding, ptr::null_mut(), 0)
+        };
+        let mut glyphs: Vec<GlyphId> = vec![0; count as usize];
+        unsafe {
+            sys::sk_font_text_to_glyphs(self.0, ptr, len, encoding, glyphs.as_mut_ptr(), count);
+        }
+        glyphs
+    }
+    pub fn unichars_to_glyphs(&self, chars: &[char]) -> Vec<GlyphId> {
+        self.text_to_glyphs(EncodedText::Utf32(chars))
+    }
+    pub fn unichar_to_glyph(&self, c: char) -> GlyphId {
+        self.unichars_to_glyphs(&[c])[0]
+    }
+
+    /// The advance width of each glyph.
+    pub fn glyph_widths(&self, glyphs: &[GlyphId], paint: Option<&Paint>) -> Vec<f32> {
+        let mut widths = vec![0.0f32; glyphs.len()];
+        let paint_ptr = paint.map(|p| p.0 ).unwrap_or(ptr::null_mut());
+        unsafe {
+            sys::sk_font_get_widths_bounds(self.0, glyphs.as_ptr(), glyphs.len() as libc::c_int,
+                                           widths.as_mut_ptr(), ptr::null_mut(), paint_ptr);
+        }
+        widths
+    }
+    /// The bounds of each glyph relative to its origin. If `paint` is given
+    /// its stroke and effects are included.
+    pub fn glyph_bounds(&self, glyphs: &[GlyphId], paint: Option<&Paint>) -> Vec<FRect> {
+        let mut bounds: Vec<sys::Rect> = vec![Default::default(); glyphs.len()];
+        let paint_ptr = paint.map(|p| p.0 ).unwrap_or(ptr::null_mut());
+        unsafe {
+            sys::sk_font_get_widths_bounds(self.0, glyphs.as_ptr(), glyphs.len() as libc::c_int,
+                                           ptr::null_mut(), bounds.as_mut_ptr(), paint_ptr);
+        }
+        bounds.into_iter().map(from_ffi_frect).collect()
+    }
+    /// The origin of each glyph when drawn one after the other starting at
+    /// `origin`.
+    pub fn glyph_positions(&self, glyphs: &[GlyphId], origin: FPoint) -> Vec<FPoint> {
+        let mut pos: Vec<sys::Point> = vec![Default::default(); glyphs.len()];
+        unsafe {
+            sys::sk_font_get_pos(self.0, glyphs.as_ptr(), glyphs.len() as libc::c_int,
+                                 pos.as_mut_ptr(), to_ffi_point(origin));
+        }
+        pos.into_iter().map(from_ffi_point).collect()
+    }
+
+    /// The outline of `glyph` at this font's size, with its origin at 0, 0.
+    /// Returns `None` for glyphs without an outline, eg bitmap glyphs.
+    pub fn glyph_path(&self, glyph: GlyphId) -> Option<Path> {
+        let path: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_font_get_path(self.0, glyph, path.0)
+        };
+        if ok { Some(path) } else { None }
+    }
+    /// Call `f` with each glyph, its unscaled outline if it has one, and the
+    /// matrix which scales the outline to this font's size. The outlines are
+    /// copied out of Skia's glyph cache before `f` is called.
+    pub fn glyph_paths<F>(&self, glyphs: &[GlyphId], mut f: F)
+        where F: FnMut(GlyphId, Option<&Path>, &Matrix),
+    {
+        extern "C" fn glyph_path_proc(path: *const sys::PathRef, m: *const sys::Matrix,
+                                      ctxt: *mut libc::c_void) {
+            let outlines = unsafe { &mut *(ctxt as *mut Vec<(Option<Path>, Matrix)>) };
+            // The path is owned by Skia, so is copied rather than dropped.
+            let path = if path == ptr::null() {
+                None
+            } else {
+                Some((*::std::mem::ManuallyDrop::new(Path(path as sys::Path))).clone())
+            };
+            outlines.push((path, from_ffi_matrix(unsafe { *m })));
+        }
+
+        let mut outlines: Vec<(Option<Path>, Matrix)> = Vec::with_capacity(glyphs.len());
+        unsafe {
+            sys::sk_font_get_paths(self.0, glyphs.as_ptr(), glyphs.len() as libc::c_int,
+                                   glyph_path_proc, &mut outlines as *mut Vec<_> as *mut _);
+        }
+        // `f` isn't called from `glyph_path_proc`, as a panic can't unwind
+        // through Skia.
+        for (&glyph, &(ref path, ref m)) in glyphs.iter().zip(outlines.iter()) {
+            f(glyph, path.as_ref(), m);
+        }
+    }
+    /// The outlines of `text` laid out from `origin`, as one path.
+    pub fn text_to_path<'a, T>(&self, text: T, origin: FPoint) -> Path
+        where T: Into<EncodedText<'a>>,
+    {
+        let glyphs = self.text_to_glyphs(text);
+        let positions = self.glyph_positions(&glyphs[..], origin);
+        let mut out: Path = Default::default();
+        let mut idx = 0;
+        self.glyph_paths(&glyphs[..], |_, path, m| {
+            if let Some(path) = path {
+                let p = positions[idx];
+                let m = Matrix::new(m.m11, m.m12, m.m21, m.m22, m.m31 + p.x, m.m32 + p.y);
+                out.add_path(path, Some(&m), AddPathMode::Append);
+            }
+            idx += 1;
+        });
+        out
+    }
+}
@@ -0,0 +1,139 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Font discovery and matching.
+
+use std::ptr;
+
+use libc;
+use sys;
+
+use super::{Typeface, FontStyle, ffi_string};
+
+fn typeface_or_none(tf: sys::Typeface) -> Option<Typeface> {
+    if tf == ptr::null_mut() {
+        None
+    } else {
+        Some(Typeface(tf))
+    }
+}
+
+/// Aka `SkFontMgr`. Enumerates the fonts available on the system and
+/// matches requests against them.
+pub struct FontMgr(sys::FontMgr);
+unsafe impl Send for FontMgr { }
+impl Clone for FontMgr {
+    fn clone(&self) -> FontMgr {
+        unsafe {
+            sys::sk_font_mgr_ref(self.0);
+        }
+        FontMgr(self.0)
+    }
+}
+impl Drop for FontMgr {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_font_mgr_unref(self.0);
+        }
+    }
+}
+impl Default for FontMgr {
+    /// The platform's font manager.
+    fn default() -> FontMgr {
+        FontMgr(unsafe {
+            sys::sk_font_mgr_ref_default()
+        })
+    }
+}
+impl FontMgr {
+    pub fn count_families(&self) -> usize {
+        unsafe {
+            sys::sk_font_mgr_count_families(self.0) as usize
+        }
+    }
+    pub fn family_names(&self) -> Vec<String> {
+        (0..self.count_families())
+            .map(|i| ffi_string(|s| unsafe {
+                sys::sk_font_mgr_get_family_name(self.0, i as libc::c_int, s)
+            }))
+            .collect()
+    }
+    /// The styles available for the family `name`. The set is empty if there
+    /// is no such family.
+    pub fn match_family(&self, name: &str) -> FontStyleSet {
+        FontStyleSet(unsafe {
+            sys::sk_font_mgr_match_family(self.0, name.as_ptr() as *const _, name.len())
+        })
+    }
+    /// The closest match to `style` within the family `name`. Returns `None`
+    /// if there is no such family.
+    pub fn match_family_style(&self, name: &str, style: FontStyle) -> Option<Typeface> {
+        typeface_or_none(unsafe {
+            sys::sk_font_mgr_match_family_style(self.0, name.as_ptr() as *const _, name.len(),
+                                                style.into())
+        })
+    }
+    /// Find a typeface which can render `character`, preferring the family
+    /// `name` and fonts for the BCP 47 language tags in `bcp47`, the most
+    /// preferred last. Used for fallback when the chosen typeface has no
+    /// glyph for a character.
+    pub fn match_family_style_character(&self, name: &str, style: FontStyle,
+                                        bcp47: &[&str], character: char) -> Option<Typeface> {
+        let tag_ptrs: Vec<*const libc::c_char> = bcp47.iter()
+            .map(|t| t.as_ptr() as *const _ )
+            .collect();
+        let tag_lens: Vec<libc::size_t> = bcp47.iter()
+            .map(|t| t.len() )
+            .collect();
+        typeface_or_none(unsafe {
+            sys::sk_font_mgr_match_family_style_character(self.0, name.as_ptr() as *const _,
+                                                          name.len(), style.into(),
+                                                          tag_ptrs.as_ptr(), tag_lens.as_ptr(),
+                                                          bcp47.len() as libc::c_int,
+                                                          character as i32)
+        })
+    }
+}
+
+/// Aka `SkFontStyleSet`. The styles of a single font family.
+pub struct FontStyleSet(sys::FontStyleSet);
+impl Drop for FontStyleSet {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_font_style_set_unref(self.0);
+        }
+    }
+}
+impl FontStyleSet {
+    pub fn len(&self) -> usize {
+        unsafe {
+            sys::sk_font_style_set_count(self.0) as usize
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The style and style name, eg "Bold Italic", of the `idx`th typeface.
+    pub fn style(&self, idx: usize) -> (FontStyle, String) {
+        let mut style: sys::FontStyle = FontStyle::NORMAL.into();
+        let name = ffi_string(|s| unsafe {
+            sys::sk_font_style_set_get_style(self.0, idx as libc::c_int,
+                                             &mut style as *mut _, s)
+        });
+        (From::from(style), name)
+    }
+    pub fn styles(&self) -> Vec<(FontStyle, String)> {
+        (0..self.len()).map(|i| self.style(i) ).collect()
+    }
+    pub fn create_typeface(&self, idx: usize) -> Option<Typeface> {
+        typeface_or_none(unsafe {
+            sys::sk_font_style_set_create_typeface(self.0, idx as libc::c_int)
+        })
+    }
+    pub fn match_style(&self, style: FontStyle) -> Option<Typeface> {
+        typeface_or_none(unsafe {
+            sys::sk_font_style_set_match_style(self.0, style.into())
+        })
+    }
+}
@@ -51,8 +51,10 @@ impl Context {
     pub fn create_budgeted_offscreen_surface(&self,
                                              info: ImageInfo) -> Option<BasicSurface> {
         let surface = unsafe {
-            sys::sk_new_render_target_surface(self.0, sys::CacheManagement::Budgeted,
-                                              info.into())
+            sys::sk_new_render_target_surface_with_color_space(self.0,
+                                                               sys::CacheManagement::Budgeted,
+                                                               (&info).into(),
+                                                               info.ffi_color_space())
         };
         if surface == ptr::null_mut() {
             None
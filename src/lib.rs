@@ -19,13 +19,17 @@ extern crate glx;
 #[cfg(target_os="android")]
 extern crate egl;
 
-extern crate skia_sys as sys;
+extern crate skia_sys;
 extern crate glium;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+
 use std::ptr;
 
 pub use sys::{ColorType, AlphaType, ColorProfile, TypefaceStyle,
-              Align, PaintStyle,};
+              Align, PaintStyle, PathDirection, ArcSize, AddPathMode,
+              PathVerb, PathOp, StrokeCap, StrokeJoin,};
 
 mod skia {
     pub use sys::*;
@@ -33,6 +37,19 @@ mod skia {
 
 pub mod gr;
 
+pub mod path_measure;
+pub mod bitmap;
+pub mod font_mgr;
+pub mod font;
+pub mod text_blob;
+
+mod sys;
+mod color;
+mod svg;
+mod sfnt;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 pub mod gl_context;
 pub mod gl_rasterization_context;
 
@@ -56,14 +73,20 @@ pub mod gl_context_wgl;
 #[cfg(target_os="windows")]
 pub mod gl_rasterization_context_wgl;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct ImageInfo {
     pub size: euclid::Size2D<i32>,
     pub color_type: ColorType,
     pub alpha_type: AlphaType,
     pub color_profile: ColorProfile,
+    /// Pixels are in this color space. If `None`, drawing isn't color managed
+    /// and colors are copied as is. As `ColorSpace` is reference counted,
+    /// `ImageInfo` is `Clone` but not `Copy`.
+    pub color_space: Option<ColorSpace>,
 }
-impl Into<sys::ImageInfo> for ImageInfo {
+/// `sys::ImageInfo` has no color space; FFI calls which need it take
+/// `ffi_color_space()` separately.
+impl<'a> Into<sys::ImageInfo> for &'a ImageInfo {
     fn into(self) -> sys::ImageInfo {
         sys::ImageInfo {
             width: self.size.width,
@@ -74,14 +97,10 @@ impl Into<sys::ImageInfo> for ImageInfo {
         }
     }
 }
+/// The color space is `None`; see `ImageInfo::from_ffi`.
 impl From<sys::ImageInfo> for ImageInfo {
     fn from(f: sys::ImageInfo) -> ImageInfo {
-        ImageInfo {
-            size: euclid::Size2D::new(f.width, f.height),
-            color_type: f.color_type,
-            alpha_type: f.alpha_type,
-            color_profile: f.color_profile,
-        }
+        ImageInfo::from_ffi(f, ptr::null_mut())
     }
 }
 impl Default for ImageInfo {
@@ -90,10 +109,97 @@ impl Default for ImageInfo {
         From::from(sys)
     }
 }
+impl ImageInfo {
+    /// Takes ownership of a `color_space` reference, which may be null.
+    fn from_ffi(info: sys::ImageInfo, color_space: sys::ColorSpace) -> ImageInfo {
+        ImageInfo {
+            size: euclid::Size2D::new(info.width, info.height),
+            color_type: info.color_type,
+            alpha_type: info.alpha_type,
+            color_profile: info.color_profile,
+            color_space: if color_space == ptr::null_mut() {
+                None
+            } else {
+                Some(ColorSpace(color_space))
+            },
+        }
+    }
+    /// Borrowed, so it must not outlive `self`. Null if `None`.
+    fn ffi_color_space(&self) -> sys::ColorSpace {
+        self.color_space.as_ref()
+            .map(|cs| cs.0 )
+            .unwrap_or(ptr::null_mut())
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        unsafe {
+            sys::sk_color_type_bytes_per_pixel(self.color_type) as usize
+        }
+    }
+    /// The smallest row stride which can hold a row of pixels. Returns `None`
+    /// if the width is negative or the stride doesn't fit in a `usize`.
+    pub fn min_row_bytes(&self) -> Option<usize> {
+        if self.size.width < 0 {
+            return None;
+        }
+        (self.size.width as usize).checked_mul(self.bytes_per_pixel())
+    }
+    /// The number of bytes needed to hold the pixels with a row stride of
+    /// `row_bytes`. The last row isn't padded. Returns `None` if either
+    /// dimension is negative or the size doesn't fit in a `usize`.
+    pub fn compute_byte_size(&self, row_bytes: usize) -> Option<usize> {
+        if self.size.width < 0 || self.size.height < 0 {
+            None
+        } else if self.size.width == 0 || self.size.height == 0 {
+            Some(0)
+        } else {
+            let rows = row_bytes.checked_mul(self.size.height as usize - 1)?;
+            rows.checked_add(self.min_row_bytes()?)
+        }
+    }
+
+    fn check_pixels(&self, len: usize, row_bytes: usize) -> Result<(), Error> {
+        if self.size.width <= 0 || self.size.height <= 0 {
+            return Err(Error::Pixels);
+        }
+        let min_row_bytes = self.min_row_bytes().ok_or(Error::Pixels)?;
+        let byte_size = self.compute_byte_size(row_bytes).ok_or(Error::Pixels)?;
+        if row_bytes < min_row_bytes || len < byte_size {
+            Err(Error::Pixels)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Copy `src` pixels into `dst`, converting between the color types, alpha
+/// types and color spaces of the respective infos. The infos must have the
+/// same size. Fails with `Error::ColorType` if Skia can't perform the
+/// conversion, eg from a color type to one without alpha or to `Unknown`.
+pub fn convert_pixels(dst_info: &ImageInfo, dst: &mut [u8], dst_row_bytes: usize,
+                      src_info: &ImageInfo, src: &[u8], src_row_bytes: usize)
+    -> Result<(), Error>
+{
+    if dst_info.size != src_info.size {
+        return Err(Error::Pixels);
+    }
+    dst_info.check_pixels(dst.len(), dst_row_bytes)?;
+    src_info.check_pixels(src.len(), src_row_bytes)?;
+
+    let ok = unsafe {
+        sys::sk_convert_pixels(dst_info.into(), dst_info.ffi_color_space(),
+                               dst.as_mut_ptr() as *mut _, dst_row_bytes,
+                               src_info.into(), src_info.ffi_color_space(),
+                               src.as_ptr() as *const _, src_row_bytes)
+    };
+    if ok { Ok(()) } else { Err(Error::ColorType) }
+}
 
 pub type FPoint = euclid::point::Point2D<f32>;
 pub type FRect = euclid::SideOffsets2D<f32>;
+pub type IRect = euclid::SideOffsets2D<i32>;
 pub type ISize = euclid::Size2D<i32>;
+pub type Matrix = euclid::Matrix2D<f32>;
 
 fn to_ffi_point(p: FPoint) -> sys::Point {
     sys::Point {
@@ -123,6 +229,32 @@ fn from_ffi_frect(r: sys::Rect) -> FRect {
         right: r.right,
     }
 }
+fn to_ffi_matrix(m: &Matrix) -> sys::Matrix {
+    sys::Matrix {
+        scale_x: m.m11,
+        skew_x: m.m21,
+        trans_x: m.m31,
+        skew_y: m.m12,
+        scale_y: m.m22,
+        trans_y: m.m32,
+        persp_0: 0.0,
+        persp_1: 0.0,
+        persp_2: 1.0,
+    }
+}
+fn to_ffi_irect(r: IRect) -> sys::IRect {
+    sys::IRect {
+        top: r.top,
+        bottom: r.bottom,
+        left: r.left,
+        right: r.right,
+    }
+}
+fn from_ffi_matrix(m: sys::Matrix) -> Matrix {
+    Matrix::new(m.scale_x, m.skew_y,
+                m.skew_x, m.scale_y,
+                m.trans_x, m.trans_y)
+}
 #[allow(dead_code)]
 fn to_ffi_isize(p: ISize) -> sys::ISize {
     sys::ISize {
@@ -137,10 +269,35 @@ fn from_ffi_isize(p: sys::ISize) -> ISize {
     }
 }
 
+/// Run `f` with a new `SkString`, and copy out what it leaves there.
+fn ffi_string<F>(f: F) -> String
+    where F: FnOnce(sys::String),
+{
+    unsafe {
+        let s = sys::sk_string_new();
+        f(s);
+        let mut len: libc::size_t = 0;
+        let data = sys::sk_string_data(s, &mut len as *mut _);
+        let bytes = std::slice::from_raw_parts(data as *const u8, len);
+        let out = String::from_utf8_lossy(bytes).into_owned();
+        sys::sk_string_delete(s);
+        out
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Error {
     Unknown,
     ColorType,
+    /// Malformed SVG path data.
+    SvgPath,
+    /// Unrecognized CSS color string.
+    CssColor,
+    /// A pixel buffer is too small for its `ImageInfo` and row stride, or the
+    /// source and destination sizes differ.
+    Pixels,
+    /// Attempted to modify an immutable bitmap.
+    Immutable,
 }
 
 /// Aka `SkSurface`/`SkCanvas`
@@ -175,7 +332,10 @@ impl RasterizedSurface {
 
         let row_size = info.size.width as usize * std::mem::size_of::<(u8, u8, u8, u8)>();
         let ptr = unsafe {
-            sys::sk_new_raster_direct_surface(info.into(), dest.as_mut_ptr() as *mut _, row_size)
+            sys::sk_new_raster_direct_surface_with_color_space((&info).into(),
+                                                               info.ffi_color_space(),
+                                                               dest.as_mut_ptr() as *mut _,
+                                                               row_size)
         };
         if ptr == ptr::null_mut() {
             Err(Error::Unknown)
@@ -198,10 +358,55 @@ pub struct Paint(sys::Paint);
 pub struct Path(sys::Path);
 pub struct Image(sys::Image);
 pub struct Typeface(sys::Typeface);
+/// An unpremultiplied 8-bit `0xAARRGGBB` color.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Color(sys::Color);
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+/// A rectangle with elliptical corners. `radii` are ordered upper-left,
+/// upper-right, lower-right, lower-left.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RRect {
+    pub rect: FRect,
+    pub radii: [FPoint; 4],
+}
+impl RRect {
+    pub fn new_rect_xy(rect: FRect, rx: f32, ry: f32) -> RRect {
+        let r = euclid::point::Point2D::new(rx, ry);
+        RRect {
+            rect: rect,
+            radii: [r; 4],
+        }
+    }
+}
+impl Into<sys::RRect> for RRect {
+    fn into(self) -> sys::RRect {
+        sys::RRect {
+            rect: to_ffi_frect(self.rect),
+            radii: [
+                to_ffi_point(self.radii[0]),
+                to_ffi_point(self.radii[1]),
+                to_ffi_point(self.radii[2]),
+                to_ffi_point(self.radii[3]),
+            ],
+        }
+    }
+}
+impl From<sys::RRect> for RRect {
+    fn from(f: sys::RRect) -> RRect {
+        RRect {
+            rect: from_ffi_frect(f.rect),
+            radii: [
+                from_ffi_point(f.radii[0]),
+                from_ffi_point(f.radii[1]),
+                from_ffi_point(f.radii[2]),
+                from_ffi_point(f.radii[3]),
+            ],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathFillType {
     Winding { inverse: bool },
     EvenOdd { inverse: bool },
@@ -307,6 +512,41 @@ pub trait Canvas: Sized {
                    points: &[FPoint]) -> &mut Self;
     fn draw_path(&mut self, paint: &Paint, path: &Path) -> &mut Self;
     fn draw_text(&mut self, paint: &Paint, pos: FPoint, text: &str) -> &mut Self;
+    /// Like `draw_text`, but with the text settings of `font` rather than
+    /// `paint`.
+    fn draw_str(&mut self, paint: &Paint, font: &font::Font, pos: FPoint, text: &str) -> &mut Self;
+    /// Like `draw_str`, for text in any encoding, including already shaped
+    /// glyphs.
+    fn draw_encoded_text(&mut self, paint: &Paint, font: &font::Font, pos: FPoint,
+                         text: font::EncodedText) -> &mut Self;
+    /// Draw already shaped glyphs at `positions`, which are relative to
+    /// `origin`. Panics if `positions` isn't the same length as `glyphs`.
+    fn draw_glyphs(&mut self, paint: &Paint, font: &font::Font, origin: FPoint,
+                   glyphs: &[font::GlyphId], positions: &[FPoint]) -> &mut Self;
+    fn draw_text_blob(&mut self, paint: &Paint, origin: FPoint,
+                      blob: &text_blob::TextBlob) -> &mut Self;
+    /// Attach an annotation to `rect`. Only document backends (ie PDF) do
+    /// anything with these; raster and GPU surfaces ignore them.
+    fn draw_annotation(&mut self, rect: FRect, key: &str, data: Option<&[u8]>) -> &mut Self;
+    /// Make `rect` a clickable link to `url`.
+    fn annotate_rect_with_url(&mut self, rect: FRect, url: &str) -> &mut Self;
+    /// Define a named destination at `point`, which can be linked to with
+    /// `annotate_link_to_destination`.
+    fn annotate_named_destination(&mut self, point: FPoint, name: &str) -> &mut Self;
+    /// Make `rect` a clickable link to the named destination `name`.
+    fn annotate_link_to_destination(&mut self, rect: FRect, name: &str) -> &mut Self;
+}
+
+pub const ANNOTATION_KEY_URL: &'static str = "SkAnnotationKey_URL";
+pub const ANNOTATION_KEY_DEFINE_NAMED_DEST: &'static str = "SkAnnotationKey_Define_Named_Dest";
+pub const ANNOTATION_KEY_LINK_NAMED_DEST: &'static str = "SkAnnotationKey_Link_Named_Dest";
+
+/// Skia expects annotation strings to be nul terminated.
+fn annotation_c_string(s: &str) -> Vec<u8> {
+    let mut data = Vec::with_capacity(s.len() + 1);
+    data.extend_from_slice(s.as_bytes());
+    data.push(0);
+    data
 }
 
 impl<'a, T> Canvas for T
@@ -318,9 +558,10 @@ impl<'a, T> Canvas for T
         })
     }
     fn image_info(&self) -> ImageInfo {
-        From::from(unsafe {
-            sys::sk_surface_get_image_info(self.basic_surface().0)
-        })
+        unsafe {
+            ImageInfo::from_ffi(sys::sk_surface_get_image_info(self.basic_surface().0),
+                                sys::sk_surface_get_color_space(self.basic_surface().0))
+        }
     }
     fn discard(&mut self) -> &mut Self {
         unsafe {
@@ -395,6 +636,62 @@ impl<'a, T> Canvas for T
         }
         self
     }
+    fn draw_str(&mut self, paint: &Paint, font: &font::Font, pos: FPoint, text: &str) -> &mut Self {
+        self.draw_encoded_text(paint, font, pos, font::EncodedText::Utf8(text))
+    }
+    fn draw_encoded_text(&mut self, paint: &Paint, font: &font::Font, pos: FPoint,
+                         text: font::EncodedText) -> &mut Self {
+        let (ptr, len, encoding) = text.to_ffi();
+        unsafe {
+            sys::sk_draw_simple_text(self.basic_surface().0, ptr, len, encoding,
+                                     to_ffi_point(pos), font.0, paint.0);
+        }
+        self
+    }
+    fn draw_glyphs(&mut self, paint: &Paint, font: &font::Font, origin: FPoint,
+                   glyphs: &[font::GlyphId], positions: &[FPoint]) -> &mut Self {
+        assert_eq!(glyphs.len(), positions.len());
+        let positions: Vec<sys::Point> = positions.iter()
+            .map(|&p| to_ffi_point(p) )
+            .collect();
+        unsafe {
+            sys::sk_draw_glyphs(self.basic_surface().0, glyphs.len() as libc::c_int,
+                                glyphs.as_ptr(), positions.as_ptr(), to_ffi_point(origin),
+                                font.0, paint.0);
+        }
+        self
+    }
+    fn draw_text_blob(&mut self, paint: &Paint, origin: FPoint,
+                      blob: &text_blob::TextBlob) -> &mut Self {
+        unsafe {
+            sys::sk_draw_text_blob(self.basic_surface().0, blob.0, origin.x, origin.y, paint.0);
+        }
+        self
+    }
+    fn draw_annotation(&mut self, rect: FRect, key: &str, data: Option<&[u8]>) -> &mut Self {
+        let (data_ptr, data_len) = data
+            .map(|d| (d.as_ptr(), d.len()) )
+            .unwrap_or((ptr::null(), 0));
+        unsafe {
+            sys::sk_draw_annotation(self.basic_surface().0, to_ffi_frect(rect),
+                                    key.as_ptr() as *const _, key.len(),
+                                    data_ptr as *const _, data_len);
+        }
+        self
+    }
+    fn annotate_rect_with_url(&mut self, rect: FRect, url: &str) -> &mut Self {
+        let data = annotation_c_string(url);
+        self.draw_annotation(rect, ANNOTATION_KEY_URL, Some(&data[..]))
+    }
+    fn annotate_named_destination(&mut self, point: FPoint, name: &str) -> &mut Self {
+        let rect = FRect::new(point.y, point.x, point.y, point.x);
+        let data = annotation_c_string(name);
+        self.draw_annotation(rect, ANNOTATION_KEY_DEFINE_NAMED_DEST, Some(&data[..]))
+    }
+    fn annotate_link_to_destination(&mut self, rect: FRect, name: &str) -> &mut Self {
+        let data = annotation_c_string(name);
+        self.draw_annotation(rect, ANNOTATION_KEY_LINK_NAMED_DEST, Some(&data[..]))
+    }
 }
 
 impl Default for Paint {
@@ -435,6 +732,18 @@ impl Paint {
             sys::sk_paint_set_color(self.0, color.into())
         };
     }
+    pub fn get_color4f(&self) -> Color4f {
+        from_ffi_color4f(unsafe {
+            sys::sk_paint_get_color4f(self.0)
+        })
+    }
+    /// `color` is unpremultiplied, in `color_space`, or sRGB if `None`.
+    pub fn set_color4f(&mut self, color: Color4f, color_space: Option<&ColorSpace>) {
+        let cs_ptr = color_space.map(|cs| cs.0 ).unwrap_or(ptr::null_mut());
+        unsafe {
+            sys::sk_paint_set_color4f(self.0, to_ffi_color4f(color), cs_ptr)
+        };
+    }
     pub fn get_typeface(&self) -> Option<Typeface> {
         let tf = unsafe {
             sys::sk_paint_get_typeface(self.0)
@@ -521,11 +830,59 @@ impl Paint {
             sys::sk_paint_set_style(self.0, s);
         }
     }
+    pub fn get_stroke_width(&self) -> f32 {
+        unsafe {
+            sys::sk_paint_get_stroke_width(self.0)
+        }
+    }
+    pub fn set_stroke_width(&mut self, w: f32) {
+        unsafe {
+            sys::sk_paint_set_stroke_width(self.0, w);
+        }
+    }
+    pub fn get_stroke_miter(&self) -> f32 {
+        unsafe {
+            sys::sk_paint_get_stroke_miter(self.0)
+        }
+    }
+    pub fn set_stroke_miter(&mut self, m: f32) {
+        unsafe {
+            sys::sk_paint_set_stroke_miter(self.0, m);
+        }
+    }
+    pub fn get_stroke_cap(&self) -> StrokeCap {
+        unsafe {
+            sys::sk_paint_get_stroke_cap(self.0)
+        }
+    }
+    pub fn set_stroke_cap(&mut self, c: StrokeCap) {
+        unsafe {
+            sys::sk_paint_set_stroke_cap(self.0, c);
+        }
+    }
+    pub fn get_stroke_join(&self) -> StrokeJoin {
+        unsafe {
+            sys::sk_paint_get_stroke_join(self.0)
+        }
+    }
+    pub fn set_stroke_join(&mut self, j: StrokeJoin) {
+        unsafe {
+            sys::sk_paint_set_stroke_join(self.0, j);
+        }
+    }
     fn set_text_encoding(&self, e: sys::TextEncoding) {
         unsafe {
             sys::sk_paint_set_text_encoding(self.0, e);
         }
     }
+    /// The metrics of the paint's typeface at its text size and scale.
+    pub fn font_metrics(&self) -> font::FontMetrics {
+        let mut m: sys::FontMetrics = Default::default();
+        unsafe {
+            sys::sk_paint_get_font_metrics(self.0, &mut m as *mut _);
+        }
+        From::from(m)
+    }
     pub fn measure_text<T>(&self, text: T, scale: Option<f32>, bounds: Option<&mut FRect>) -> f32
         where T: AsRef<str>,
     {
@@ -571,7 +928,50 @@ impl Drop for Path {
         }
     }
 }
+/// Compares fill type, verbs, and the bits of points and conic weights, with
+/// `-0.0` treated as `0.0`. Unlike Skia's `==`, a path containing NaN is equal
+/// to itself, so paths can be used as map keys.
+impl PartialEq for Path {
+    fn eq(&self, other: &Path) -> bool {
+        self.get_fill_type() == other.get_fill_type() && self.key_bits() == other.key_bits()
+    }
+}
+impl Eq for Path {}
+/// Hashes the same contents `PartialEq` compares, independent of the process,
+/// so hashes are stable as long as the hasher is.
+impl std::hash::Hash for Path {
+    fn hash<H>(&self, state: &mut H)
+        where H: std::hash::Hasher,
+    {
+        use std::hash::Hash;
+
+        self.get_fill_type().hash(state);
+        self.key_bits().hash(state);
+    }
+}
 impl Path {
+    /// The verbs, and the bits of each point and conic weight, which
+    /// `PartialEq` and `Hash` use.
+    fn key_bits(&self) -> Vec<u32> {
+        fn bits(v: f32) -> u32 {
+            // `0.0 == -0.0`, so they must compare and hash the same.
+            if v == 0.0 { 0 } else { v.to_bits() }
+        }
+
+        let mut key = Vec::with_capacity(self.points_len() * 2);
+        for seg in self.raw_iter() {
+            key.push(seg.verb() as u32);
+            for p in seg.points() {
+                key.push(bits(p.x));
+                key.push(bits(p.y));
+            }
+            if let PathSegment::Conic { weight, .. } = seg {
+                key.push(bits(weight));
+            }
+        }
+        key
+    }
+
     pub fn reset(&mut self) {
         unsafe {
             sys::sk_path_reset(self.0)
@@ -615,12 +1015,154 @@ impl Path {
         };
         self
     }
+    pub fn conic_to(&mut self, p0: FPoint, p1: FPoint, weight: f32, relative: bool) -> &mut Path {
+        unsafe {
+            sys::sk_path_conic_to(self.0, to_ffi_point(p0),
+                                  to_ffi_point(p1), weight, relative)
+        };
+        self
+    }
+    /// Append the part of the ellipse bounded by `oval` starting at
+    /// `start_angle` and sweeping `sweep_angle` degrees. If `force_move_to` is
+    /// false the arc is connected to the last point with a line.
+    pub fn arc_to(&mut self, oval: FRect, start_angle: f32, sweep_angle: f32,
+                  force_move_to: bool) -> &mut Path {
+        unsafe {
+            sys::sk_path_arc_to(self.0, to_ffi_frect(oval), start_angle, sweep_angle,
+                                force_move_to)
+        };
+        self
+    }
+    /// Append an arc of `radius` tangent to the lines from the last point to
+    /// `p1` and from `p1` to `p2`, aka the PostScript `arct`.
+    pub fn arc_to_tangent(&mut self, p1: FPoint, p2: FPoint, radius: f32) -> &mut Path {
+        unsafe {
+            sys::sk_path_arc_to_tangent(self.0, to_ffi_point(p1), to_ffi_point(p2), radius)
+        };
+        self
+    }
+    /// SVG style elliptical arc. `radii` are the ellipse's x and y radii and
+    /// `x_axis_rotate` is in degrees. `ArcSize` and `PathDirection` correspond
+    /// to SVG's large-arc and sweep flags respectively.
+    pub fn elliptical_arc_to(&mut self, radii: FPoint, x_axis_rotate: f32, size: ArcSize,
+                             sweep: PathDirection, to: FPoint, relative: bool) -> &mut Path {
+        unsafe {
+            sys::sk_path_elliptical_arc_to(self.0, to_ffi_point(radii), x_axis_rotate,
+                                           size, sweep, to_ffi_point(to), relative)
+        };
+        self
+    }
     pub fn close(&mut self) -> &mut Path {
         unsafe {
             sys::sk_path_close(self.0)
         };
         self
     }
+
+    pub fn add_rect(&mut self, rect: FRect, dir: PathDirection) -> &mut Path {
+        unsafe {
+            sys::sk_path_add_rect(self.0, to_ffi_frect(rect), dir)
+        };
+        self
+    }
+    pub fn add_oval(&mut self, oval: FRect, dir: PathDirection) -> &mut Path {
+        unsafe {
+            sys::sk_path_add_oval(self.0, to_ffi_frect(oval), dir)
+        };
+        self
+    }
+    pub fn add_circle(&mut self, center: FPoint, radius: f32, dir: PathDirection) -> &mut Path {
+        unsafe {
+            sys::sk_path_add_circle(self.0, to_ffi_point(center), radius, dir)
+        };
+        self
+    }
+    /// Like `arc_to`, but always starts a new contour.
+    pub fn add_arc(&mut self, oval: FRect, start_angle: f32, sweep_angle: f32) -> &mut Path {
+        unsafe {
+            sys::sk_path_add_arc(self.0, to_ffi_frect(oval), start_angle, sweep_angle)
+        };
+        self
+    }
+    pub fn add_rrect(&mut self, rrect: RRect, dir: PathDirection) -> &mut Path {
+        unsafe {
+            sys::sk_path_add_rrect(self.0, rrect.into(), dir)
+        };
+        self
+    }
+    pub fn add_poly(&mut self, points: &[FPoint], close: bool) -> &mut Path {
+        let points: Vec<sys::Point> = points.iter()
+            .map(|&p| to_ffi_point(p) )
+            .collect();
+        unsafe {
+            sys::sk_path_add_poly(self.0, points.as_ptr(), points.len() as libc::c_int, close)
+        };
+        self
+    }
+    /// Append `src`, transformed by `matrix` if present. With
+    /// `AddPathMode::Extend` the first contour of `src` continues this path's
+    /// last contour instead of starting a new one.
+    pub fn add_path(&mut self, src: &Path, matrix: Option<&Matrix>,
+                    mode: AddPathMode) -> &mut Path {
+        let matrix = matrix.map(|m| to_ffi_matrix(m) );
+        let matrix_ptr = matrix.as_ref()
+            .map(|m| m as *const sys::Matrix )
+            .unwrap_or(ptr::null());
+        unsafe {
+            sys::sk_path_add_path(self.0, src.0, matrix_ptr, mode)
+        };
+        self
+    }
+    /// Append `src` with its contours reversed.
+    pub fn reverse_add_path(&mut self, src: &Path) -> &mut Path {
+        unsafe {
+            sys::sk_path_reverse_add_path(self.0, src.0)
+        };
+        self
+    }
+
+    pub fn transform(&mut self, matrix: &Matrix) -> &mut Path {
+        unsafe {
+            sys::sk_path_transform(self.0, to_ffi_matrix(matrix))
+        };
+        self
+    }
+    pub fn transformed(&self, matrix: &Matrix) -> Path {
+        let mut p = self.clone();
+        p.transform(matrix);
+        p
+    }
+    pub fn offset(&mut self, dx: f32, dy: f32) -> &mut Path {
+        unsafe {
+            sys::sk_path_offset(self.0, dx, dy)
+        };
+        self
+    }
+    /// Can this path be interpolated with `other`? They must have the same
+    /// verbs and conic weights.
+    pub fn is_interpolatable(&self, other: &Path) -> bool {
+        unsafe {
+            sys::sk_path_is_interpolatable(self.0, other.0)
+        }
+    }
+    /// Interpolate between this path and `other`. A `weight` of 1 gives this
+    /// path, 0 gives `other`. Returns `None` if the paths aren't
+    /// interpolatable.
+    pub fn interpolate(&self, other: &Path, weight: f32) -> Option<Path> {
+        let out: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_path_interpolate(self.0, other.0, weight, out.0)
+        };
+        if ok { Some(out) } else { None }
+    }
+    /// Hint that this path will be drawn only a few times before changing, so
+    /// the GPU backend shouldn't bother caching it.
+    pub fn set_is_volatile(&mut self, v: bool) -> &mut Path {
+        unsafe {
+            sys::sk_path_set_is_volatile(self.0, v)
+        };
+        self
+    }
     pub fn points_len(&self) -> usize {
         unsafe {
             sys::sk_path_count_points(self.0) as usize
@@ -631,12 +1173,312 @@ impl Path {
             sys::sk_path_get_point(self.0, idx as libc::c_int)
         })
     }
-    pub fn image_info(&self) -> ImageInfo {
-        From::from(unsafe {
-            sys::sk_surface_get_image_info(self.0)
+    pub fn last_point(&self) -> Option<FPoint> {
+        let mut p: sys::Point = Default::default();
+        let ok = unsafe {
+            sys::sk_path_get_last_point(self.0, &mut p as *mut _)
+        };
+        if ok { Some(from_ffi_point(p)) } else { None }
+    }
+
+    /// The bounds of all points, including control points. Cheap, as Skia
+    /// caches it.
+    pub fn bounds(&self) -> FRect {
+        from_ffi_frect(unsafe {
+            sys::sk_path_get_bounds(self.0)
+        })
+    }
+    /// The smallest bounds enclosing the path's curves, which may be smaller
+    /// than `bounds` when control points lie outside the curve.
+    pub fn tight_bounds(&self) -> FRect {
+        from_ffi_frect(unsafe {
+            sys::sk_path_compute_tight_bounds(self.0)
+        })
+    }
+    /// Is `p` inside the area filled by this path? The fill type is respected.
+    pub fn contains(&self, p: FPoint) -> bool {
+        unsafe {
+            sys::sk_path_contains(self.0, p.x, p.y)
+        }
+    }
+    /// Returns true if `rect` is definitely inside the path. May return false
+    /// for rects which are inside, but will never return true for rects which
+    /// aren't.
+    pub fn conservatively_contains_rect(&self, rect: FRect) -> bool {
+        unsafe {
+            sys::sk_path_conservatively_contains_rect(self.0, to_ffi_frect(rect))
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        unsafe {
+            sys::sk_path_is_empty(self.0)
+        }
+    }
+    /// Are all points finite, ie not infinite or NaN?
+    pub fn is_finite(&self) -> bool {
+        unsafe {
+            sys::sk_path_is_finite(self.0)
+        }
+    }
+    pub fn is_convex(&self) -> bool {
+        unsafe {
+            sys::sk_path_is_convex(self.0)
+        }
+    }
+    pub fn is_rect(&self) -> Option<FRect> {
+        let mut r: sys::Rect = Default::default();
+        let ok = unsafe {
+            sys::sk_path_is_rect(self.0, &mut r as *mut _)
+        };
+        if ok { Some(from_ffi_frect(r)) } else { None }
+    }
+    /// Returns the oval's bounds if this path describes an oval or circle.
+    pub fn is_oval(&self) -> Option<FRect> {
+        let mut r: sys::Rect = Default::default();
+        let ok = unsafe {
+            sys::sk_path_is_oval(self.0, &mut r as *mut _)
+        };
+        if ok { Some(from_ffi_frect(r)) } else { None }
+    }
+    pub fn is_rrect(&self) -> Option<RRect> {
+        let mut r: sys::RRect = Default::default();
+        let ok = unsafe {
+            sys::sk_path_is_rrect(self.0, &mut r as *mut _)
+        };
+        if ok { Some(From::from(r)) } else { None }
+    }
+    /// Returns the end points if this path is a single line.
+    pub fn is_line(&self) -> Option<(FPoint, FPoint)> {
+        let mut pts: [sys::Point; 2] = Default::default();
+        let ok = unsafe {
+            sys::sk_path_is_line(self.0, pts.as_mut_ptr())
+        };
+        if ok {
+            Some((from_ffi_point(pts[0]), from_ffi_point(pts[1])))
+        } else {
+            None
+        }
+    }
+
+    /// Changes whenever the path's contents change. Copies share an id until
+    /// one of them is modified. Not stable across process restarts.
+    pub fn generation_id(&self) -> u32 {
+        unsafe {
+            sys::sk_path_get_generation_id(self.0)
+        }
+    }
+    /// Serialize to Skia's binary path format, which `deserialize` reads.
+    pub fn serialize(&self) -> Vec<u8> {
+        let len = unsafe {
+            sys::sk_path_write_to_memory(self.0, ptr::null_mut())
+        };
+        let mut out = vec![0u8; len];
+        unsafe {
+            sys::sk_path_write_to_memory(self.0, out.as_mut_ptr() as *mut _);
+        }
+        out
+    }
+    /// Returns `None` if `data` isn't a valid serialized path.
+    pub fn deserialize(data: &[u8]) -> Option<Path> {
+        let path: Path = Default::default();
+        let read = unsafe {
+            sys::sk_path_read_from_memory(path.0, data.as_ptr() as *const _, data.len())
+        };
+        if read == 0 { None } else { Some(path) }
+    }
+
+    /// Parse SVG path data, ie the contents of a `<path>`'s `d` attribute.
+    pub fn from_svg(d: &str) -> Result<Path, Error> {
+        svg::parse(d)
+    }
+    /// Serialize to SVG path data using either absolute or relative commands.
+    /// Conics, which SVG has no equivalent of, are written as quads.
+    pub fn to_svg(&self, absolute: bool) -> String {
+        svg::serialize(self, absolute)
+    }
+
+    /// Combine this path with `other` using `op`. Returns `None` if Skia
+    /// couldn't compute the result.
+    pub fn op(&self, other: &Path, op: PathOp) -> Option<Path> {
+        let result: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_path_op(self.0, other.0, op, result.0)
+        };
+        if ok { Some(result) } else { None }
+    }
+    /// Compute a path with the same filled area but without overlapping
+    /// contours.
+    pub fn simplify(&self) -> Option<Path> {
+        let result: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_path_simplify(self.0, result.0)
+        };
+        if ok { Some(result) } else { None }
+    }
+    /// Compute an equivalent path with a non-inverse winding fill type.
+    pub fn as_winding(&self) -> Option<Path> {
+        let result: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_path_as_winding(self.0, result.0)
+        };
+        if ok { Some(result) } else { None }
+    }
+
+    /// Iterate over the segments of this path. If `force_close` is true, each
+    /// open contour is closed with a line segment back to its start.
+    pub fn iter(&self, force_close: bool) -> PathIter {
+        PathIter {
+            iter: unsafe { sys::sk_path_iter_new(self.0, force_close) },
+            _path: std::marker::PhantomData,
+        }
+    }
+    /// Iterate over the verbs and points exactly as they are stored; unlike
+    /// `iter`, degenerate segments are kept and no closing lines are added.
+    pub fn raw_iter(&self) -> PathRawIter {
+        PathRawIter {
+            iter: unsafe { sys::sk_path_raw_iter_new(self.0) },
+            _path: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Accumulates paths and their ops so they can be combined at once, which is
+/// faster than calling `Path::op` repeatedly. The first path is combined with
+/// an empty path, so its op should usually be `PathOp::Union`.
+pub struct OpBuilder(sys::OpBuilder);
+impl Default for OpBuilder {
+    fn default() -> OpBuilder {
+        OpBuilder(unsafe {
+            sys::sk_new_op_builder()
         })
     }
 }
+impl Drop for OpBuilder {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_del_op_builder(self.0)
+        }
+    }
+}
+impl OpBuilder {
+    pub fn add(&mut self, path: &Path, op: PathOp) -> &mut OpBuilder {
+        unsafe {
+            sys::sk_op_builder_add(self.0, path.0, op)
+        };
+        self
+    }
+    /// Compute the combined path. The builder is reset afterwards.
+    pub fn resolve(&mut self) -> Option<Path> {
+        let result: Path = Default::default();
+        let ok = unsafe {
+            sys::sk_op_builder_resolve(self.0, result.0)
+        };
+        if ok { Some(result) } else { None }
+    }
+}
+
+/// Every segment other than `Move` starts with the end point of the previous
+/// segment.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PathSegment {
+    Move(FPoint),
+    Line(FPoint, FPoint),
+    Quad(FPoint, FPoint, FPoint),
+    Conic {
+        points: [FPoint; 3],
+        weight: f32,
+    },
+    Cubic(FPoint, FPoint, FPoint, FPoint),
+    Close,
+}
+impl PathSegment {
+    pub fn verb(&self) -> PathVerb {
+        match self {
+            &PathSegment::Move(..) => PathVerb::Move,
+            &PathSegment::Line(..) => PathVerb::Line,
+            &PathSegment::Quad(..) => PathVerb::Quad,
+            &PathSegment::Conic { .. } => PathVerb::Conic,
+            &PathSegment::Cubic(..) => PathVerb::Cubic,
+            &PathSegment::Close => PathVerb::Close,
+        }
+    }
+    pub fn points(&self) -> Vec<FPoint> {
+        match self {
+            &PathSegment::Move(p0) => vec![p0],
+            &PathSegment::Line(p0, p1) => vec![p0, p1],
+            &PathSegment::Quad(p0, p1, p2) => vec![p0, p1, p2],
+            &PathSegment::Conic { points, .. } => points.to_vec(),
+            &PathSegment::Cubic(p0, p1, p2, p3) => vec![p0, p1, p2, p3],
+            &PathSegment::Close => vec![],
+        }
+    }
+
+    fn from_ffi(verb: sys::PathVerb, pts: &[sys::Point; 4], weight: f32) -> Option<PathSegment> {
+        let p = |i: usize| from_ffi_point(pts[i]);
+        match verb {
+            sys::PathVerb::Move => Some(PathSegment::Move(p(0))),
+            sys::PathVerb::Line => Some(PathSegment::Line(p(0), p(1))),
+            sys::PathVerb::Quad => Some(PathSegment::Quad(p(0), p(1), p(2))),
+            sys::PathVerb::Conic => Some(PathSegment::Conic {
+                points: [p(0), p(1), p(2)],
+                weight: weight,
+            }),
+            sys::PathVerb::Cubic => Some(PathSegment::Cubic(p(0), p(1), p(2), p(3))),
+            sys::PathVerb::Close => Some(PathSegment::Close),
+            sys::PathVerb::Done => None,
+        }
+    }
+}
+
+pub struct PathIter<'a> {
+    iter: sys::PathIter,
+    _path: std::marker::PhantomData<&'a Path>,
+}
+impl<'a> Drop for PathIter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_path_iter_delete(self.iter)
+        }
+    }
+}
+impl<'a> Iterator for PathIter<'a> {
+    type Item = PathSegment;
+    fn next(&mut self) -> Option<PathSegment> {
+        let mut pts: [sys::Point; 4] = Default::default();
+        let verb = unsafe {
+            sys::sk_path_iter_next(self.iter, pts.as_mut_ptr())
+        };
+        let weight = unsafe {
+            sys::sk_path_iter_conic_weight(self.iter)
+        };
+        PathSegment::from_ffi(verb, &pts, weight)
+    }
+}
+
+pub struct PathRawIter<'a> {
+    iter: sys::PathRawIter,
+    _path: std::marker::PhantomData<&'a Path>,
+}
+impl<'a> Drop for PathRawIter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_path_raw_iter_delete(self.iter)
+        }
+    }
+}
+impl<'a> Iterator for PathRawIter<'a> {
+    type Item = PathSegment;
+    fn next(&mut self) -> Option<PathSegment> {
+        let mut pts: [sys::Point; 4] = Default::default();
+        let verb = unsafe {
+            sys::sk_path_raw_iter_next(self.iter, pts.as_mut_ptr())
+        };
+        let weight = unsafe {
+            sys::sk_path_raw_iter_conic_weight(self.iter)
+        };
+        PathSegment::from_ffi(verb, &pts, weight)
+    }
+}
 
 impl Into<sys::Color> for Color {
     fn into(self) -> sys::Color {
@@ -649,52 +1491,309 @@ impl From<sys::Color> for Color {
     }
 }
 impl Color {
-    pub fn new(a: u8, r: u8, g: u8, b: u8) -> Color {
-        Color(unsafe {
-            sys::sk_color_from_argb(a, r, g, b)
-        })
+    pub const fn new(a: u8, r: u8, g: u8, b: u8) -> Color {
+        Color((a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32)
     }
-    pub fn a(&self) -> u8 {
-        unsafe {
-            sys::sk_color_get_a(self.0) as u8
+    pub const fn a(&self) -> u8 { (self.0 >> 24) as u8 }
+    pub const fn r(&self) -> u8 { (self.0 >> 16) as u8 }
+    pub const fn g(&self) -> u8 { (self.0 >> 8) as u8 }
+    pub const fn b(&self) -> u8 { self.0 as u8 }
+
+    pub const fn set_a(self, v: u8) -> Color {
+        Color::new(v, self.r(), self.g(), self.b())
+    }
+    pub const fn set_r(self, v: u8) -> Color {
+        Color::new(self.a(), v, self.g(), self.b())
+    }
+    pub const fn set_g(self, v: u8) -> Color {
+        Color::new(self.a(), self.r(), v, self.b())
+    }
+    pub const fn set_b(self, v: u8) -> Color {
+        Color::new(self.a(), self.r(), self.g(), v)
+    }
+
+    pub fn premultiply(self) -> PMColor {
+        let a = self.a();
+        PMColor::new(a, mul_div_255_round(self.r(), a), mul_div_255_round(self.g(), a),
+                     mul_div_255_round(self.b(), a))
+    }
+}
+
+/// `a * b / 255`, rounded.
+fn mul_div_255_round(a: u8, b: u8) -> u8 {
+    let prod = a as u32 * b as u32 + 128;
+    ((prod + (prod >> 8)) >> 8) as u8
+}
+
+/// Shifts of the a, r, g and b bytes in a `PMColor`, following the byte order
+/// of `sys::NATIVE_COLOR_TYPE`.
+const PM_SHIFTS: (u32, u32, u32, u32) =
+    if matches!(sys::NATIVE_COLOR_TYPE, ColorType::RGBA8888) {
+        (24, 0, 8, 16)
+    } else {
+        (24, 16, 8, 0)
+    };
+
+/// A premultiplied 8-bit color in Skia's native 32-bit pixel layout, ie what
+/// `NATIVE_COLOR_TYPE` surfaces store.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PMColor(pub u32);
+impl PMColor {
+    /// The color channels must not exceed `a`.
+    pub const fn new(a: u8, r: u8, g: u8, b: u8) -> PMColor {
+        PMColor((a as u32) << PM_SHIFTS.0 | (r as u32) << PM_SHIFTS.1 |
+                (g as u32) << PM_SHIFTS.2 | (b as u32) << PM_SHIFTS.3)
+    }
+    pub const fn a(&self) -> u8 { (self.0 >> PM_SHIFTS.0) as u8 }
+    pub const fn r(&self) -> u8 { (self.0 >> PM_SHIFTS.1) as u8 }
+    pub const fn g(&self) -> u8 { (self.0 >> PM_SHIFTS.2) as u8 }
+    pub const fn b(&self) -> u8 { (self.0 >> PM_SHIFTS.3) as u8 }
+
+    pub fn unpremultiply(self) -> Color {
+        let a = self.a();
+        if a == 0 {
+            return Color::new(0, 0, 0, 0);
         }
+        let unpremul = |c: u8| -> u8 {
+            let v = (c as u32 * 255 + a as u32 / 2) / a as u32;
+            if v > 255 { 255 } else { v as u8 }
+        };
+        Color::new(a, unpremul(self.r()), unpremul(self.g()), unpremul(self.b()))
     }
-    pub fn r(&self) -> u8 {
+}
+
+/// A color with floating point channels, which aren't limited to `[0, 1]`
+/// when used with a wide gamut or HDR color space. Whether the color channels
+/// are premultiplied is up to the user; see `premultiply`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Color4f {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+impl Color4f {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Color4f {
+        Color4f {
+            r: r,
+            g: g,
+            b: b,
+            a: a,
+        }
+    }
+    pub fn premultiply(self) -> Color4f {
+        Color4f::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+    pub fn unpremultiply(self) -> Color4f {
+        if self.a == 0.0 {
+            Color4f::new(0.0, 0.0, 0.0, 0.0)
+        } else {
+            let inv = 1.0 / self.a;
+            Color4f::new(self.r * inv, self.g * inv, self.b * inv, self.a)
+        }
+    }
+    /// Clamps each channel to `[0, 1]` and rounds to 8 bits.
+    pub fn to_color(self) -> Color {
+        fn to_u8(v: f32) -> u8 {
+            let v = if v > 1.0 { 1.0 } else if v > 0.0 { v } else { 0.0 };
+            (v * 255.0 + 0.5) as u8
+        }
+        Color::new(to_u8(self.a), to_u8(self.r), to_u8(self.g), to_u8(self.b))
+    }
+}
+impl From<Color> for Color4f {
+    fn from(c: Color) -> Color4f {
+        let s = 1.0 / 255.0;
+        Color4f::new(c.r() as f32 * s, c.g() as f32 * s, c.b() as f32 * s, c.a() as f32 * s)
+    }
+}
+impl From<Color4f> for Color {
+    fn from(c: Color4f) -> Color {
+        c.to_color()
+    }
+}
+fn to_ffi_color4f(c: Color4f) -> sys::Color4f {
+    sys::Color4f {
+        r: c.r,
+        g: c.g,
+        b: c.b,
+        a: c.a,
+    }
+}
+fn from_ffi_color4f(c: sys::Color4f) -> Color4f {
+    Color4f::new(c.r, c.g, c.b, c.a)
+}
+
+/// Aka `SkColorSpace`. Describes how color values map to actual colors.
+pub struct ColorSpace(sys::ColorSpace);
+unsafe impl Send for ColorSpace { }
+unsafe impl Sync for ColorSpace { }
+impl Clone for ColorSpace {
+    fn clone(&self) -> ColorSpace {
         unsafe {
-            sys::sk_color_get_r(self.0) as u8
+            sys::sk_color_space_ref(self.0);
         }
+        ColorSpace(self.0)
     }
-    pub fn g(&self) -> u8 {
+}
+impl Drop for ColorSpace {
+    fn drop(&mut self) {
         unsafe {
-            sys::sk_color_get_g(self.0) as u8
+            sys::sk_color_space_unref(self.0);
         }
     }
-    pub fn b(&self) -> u8 {
+}
+impl PartialEq for ColorSpace {
+    fn eq(&self, other: &ColorSpace) -> bool {
         unsafe {
-            sys::sk_color_get_b(self.0) as u8
+            sys::sk_color_space_equals(self.0, other.0)
         }
     }
-
-    pub fn set_a(self, v: u8) -> Color {
-        Color(unsafe {
-            sys::sk_color_from_argb(v, self.r(), self.g(), self.b())
-        })
+}
+impl std::fmt::Debug for ColorSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ColorSpace")
+            .field("is_srgb", &self.is_srgb())
+            .field("gamma_is_linear", &self.gamma_is_linear())
+            .finish()
     }
-    pub fn set_r(self, v: u8) -> Color {
-        Color(unsafe {
-            sys::sk_color_from_argb(self.a(), v, self.g(), self.b())
+}
+impl ColorSpace {
+    pub fn new_srgb() -> ColorSpace {
+        ColorSpace(unsafe {
+            sys::sk_color_space_new_srgb()
         })
     }
-    pub fn set_g(self, v: u8) -> Color {
-        Color(unsafe {
-            sys::sk_color_from_argb(self.a(), self.r(), v, self.b())
+    /// sRGB primaries with a linear transfer function.
+    pub fn new_srgb_linear() -> ColorSpace {
+        ColorSpace(unsafe {
+            sys::sk_color_space_new_srgb_linear()
         })
     }
-    pub fn set_b(self, v: u8) -> Color {
-        Color(unsafe {
-            sys::sk_color_from_argb(self.a(), self.r(), self.g(), v)
+    pub fn new_display_p3() -> ColorSpace {
+        ColorSpace::new_rgb(&TransferFunction::SRGB, &Gamut::DISPLAY_P3)
+    }
+    pub fn new_rec2020() -> ColorSpace {
+        ColorSpace::new_rgb(&TransferFunction::REC2020, &Gamut::REC2020)
+    }
+    pub fn new_rgb(transfer_fn: &TransferFunction, gamut: &Gamut) -> ColorSpace {
+        let tf = sys::TransferFunction {
+            g: transfer_fn.g,
+            a: transfer_fn.a,
+            b: transfer_fn.b,
+            c: transfer_fn.c,
+            d: transfer_fn.d,
+            e: transfer_fn.e,
+            f: transfer_fn.f,
+        };
+        ColorSpace(unsafe {
+            sys::sk_color_space_new_rgb(&tf as *const _, gamut.0.as_ptr() as *const f32)
         })
     }
+    /// Create from an ICC profile. Returns `None` if the profile is invalid or
+    /// isn't an RGB profile Skia can represent.
+    pub fn new_from_icc(icc: &[u8]) -> Option<ColorSpace> {
+        let cs = unsafe {
+            sys::sk_color_space_new_icc(icc.as_ptr() as *const _, icc.len())
+        };
+        if cs == ptr::null_mut() {
+            None
+        } else {
+            Some(ColorSpace(cs))
+        }
+    }
+
+    pub fn is_srgb(&self) -> bool {
+        unsafe {
+            sys::sk_color_space_is_srgb(self.0)
+        }
+    }
+    pub fn gamma_is_linear(&self) -> bool {
+        unsafe {
+            sys::sk_color_space_gamma_is_linear(self.0)
+        }
+    }
+    /// Serialize to Skia's binary color space format, which `deserialize`
+    /// reads.
+    pub fn serialize(&self) -> Vec<u8> {
+        let len = unsafe {
+            sys::sk_color_space_write_to_memory(self.0, ptr::null_mut())
+        };
+        let mut out = vec![0u8; len];
+        unsafe {
+            sys::sk_color_space_write_to_memory(self.0, out.as_mut_ptr() as *mut _);
+        }
+        out
+    }
+    pub fn deserialize(data: &[u8]) -> Option<ColorSpace> {
+        let cs = unsafe {
+            sys::sk_color_space_deserialize(data.as_ptr() as *const _, data.len())
+        };
+        if cs == ptr::null_mut() {
+            None
+        } else {
+            Some(ColorSpace(cs))
+        }
+    }
+}
+
+/// A parametric transfer function, mapping encoded values `x` to linear:
+/// `c * x + f` for `x < d`, and `(a * x + b)^g + e` otherwise.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TransferFunction {
+    pub g: f32,
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+impl TransferFunction {
+    pub const SRGB: TransferFunction = TransferFunction {
+        g: 2.4, a: 1.0 / 1.055, b: 0.055 / 1.055, c: 1.0 / 12.92, d: 0.04045, e: 0.0, f: 0.0,
+    };
+    pub const GAMMA_2_2: TransferFunction = TransferFunction {
+        g: 2.2, a: 1.0, b: 0.0, c: 0.0, d: 0.0, e: 0.0, f: 0.0,
+    };
+    pub const LINEAR: TransferFunction = TransferFunction {
+        g: 1.0, a: 1.0, b: 0.0, c: 0.0, d: 0.0, e: 0.0, f: 0.0,
+    };
+    pub const REC2020: TransferFunction = TransferFunction {
+        g: 2.22222, a: 0.909672, b: 0.0903276, c: 0.222222, d: 0.0812429, e: 0.0, f: 0.0,
+    };
+}
+
+/// A color gamut, as the row major matrix converting its linear RGB to XYZ
+/// (D50).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Gamut(pub [[f32; 3]; 3]);
+impl Gamut {
+    pub const SRGB: Gamut = Gamut([
+        [0.436065674, 0.385147095, 0.143066406],
+        [0.222488403, 0.716873169, 0.060607910],
+        [0.013916016, 0.097076416, 0.714096069],
+    ]);
+    pub const ADOBE_RGB: Gamut = Gamut([
+        [0.60974, 0.20528, 0.14919],
+        [0.31111, 0.62567, 0.06322],
+        [0.01947, 0.06087, 0.74457],
+    ]);
+    pub const DISPLAY_P3: Gamut = Gamut([
+        [0.515102, 0.291965, 0.157153],
+        [0.241182, 0.692236, 0.0665819],
+        [-0.00104941, 0.0418818, 0.784378],
+    ]);
+    pub const REC2020: Gamut = Gamut([
+        [0.673459, 0.165661, 0.125100],
+        [0.279033, 0.675338, 0.0456288],
+        [-0.00193139, 0.0299794, 0.797162],
+    ]);
+    pub const XYZ: Gamut = Gamut([
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ]);
 }
 
 fn color_type_to_gl(ct: sys::ColorType) -> Option<glium::texture::UncompressedFloatFormat> {
@@ -726,6 +1825,22 @@ impl Image {
         }
         from_ffi_isize(size)
     }
+    pub fn image_info(&self) -> ImageInfo {
+        unsafe {
+            ImageInfo::from_ffi(sys::sk_image_get_image_info(self.0),
+                                sys::sk_image_get_color_space(self.0))
+        }
+    }
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        let cs = unsafe {
+            sys::sk_image_get_color_space(self.0)
+        };
+        if cs == ptr::null_mut() {
+            None
+        } else {
+            Some(ColorSpace(cs))
+        }
+    }
     /// The returned texture object is only valid as long as the image is alive.
     pub unsafe fn get_backing_texture_handle<F>(&self, f: &F, info: ImageInfo) -> Option<glium::Texture2d>
         where F: glium::backend::Facade,
@@ -747,7 +1862,172 @@ impl Image {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontWidth {
+    UltraCondensed = 1,
+    ExtraCondensed = 2,
+    Condensed = 3,
+    SemiCondensed = 4,
+    Normal = 5,
+    SemiExpanded = 6,
+    Expanded = 7,
+    ExtraExpanded = 8,
+    UltraExpanded = 9,
+}
+impl FontWidth {
+    /// Out of range values are clamped.
+    fn from_ffi(w: libc::c_int) -> FontWidth {
+        match w {
+            i32::MIN..=1 => FontWidth::UltraCondensed,
+            2 => FontWidth::ExtraCondensed,
+            3 => FontWidth::Condensed,
+            4 => FontWidth::SemiCondensed,
+            5 => FontWidth::Normal,
+            6 => FontWidth::SemiExpanded,
+            7 => FontWidth::Expanded,
+            8 => FontWidth::ExtraExpanded,
+            _ => FontWidth::UltraExpanded,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Slant {
+    Upright,
+    Italic,
+    Oblique,
+}
+
+/// A font's weight, width and slant, as in CSS's `font-weight`, `font-stretch`
+/// and `font-style`. Weights range from 1 to 1000.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FontStyle {
+    pub weight: u16,
+    pub width: FontWidth,
+    pub slant: Slant,
+}
+impl FontStyle {
+    pub const WEIGHT_INVISIBLE: u16 = 0;
+    pub const WEIGHT_THIN: u16 = 100;
+    pub const WEIGHT_EXTRA_LIGHT: u16 = 200;
+    pub const WEIGHT_LIGHT: u16 = 300;
+    pub const WEIGHT_NORMAL: u16 = 400;
+    pub const WEIGHT_MEDIUM: u16 = 500;
+    pub const WEIGHT_SEMI_BOLD: u16 = 600;
+    pub const WEIGHT_BOLD: u16 = 700;
+    pub const WEIGHT_EXTRA_BOLD: u16 = 800;
+    pub const WEIGHT_BLACK: u16 = 900;
+    pub const WEIGHT_EXTRA_BLACK: u16 = 1000;
+
+    pub const NORMAL: FontStyle = FontStyle::new(FontStyle::WEIGHT_NORMAL, FontWidth::Normal,
+                                                 Slant::Upright);
+    pub const BOLD: FontStyle = FontStyle::new(FontStyle::WEIGHT_BOLD, FontWidth::Normal,
+                                               Slant::Upright);
+    pub const ITALIC: FontStyle = FontStyle::new(FontStyle::WEIGHT_NORMAL, FontWidth::Normal,
+                                                 Slant::Italic);
+    pub const BOLD_ITALIC: FontStyle = FontStyle::new(FontStyle::WEIGHT_BOLD, FontWidth::Normal,
+                                                      Slant::Italic);
+
+    pub const fn new(weight: u16, width: FontWidth, slant: Slant) -> FontStyle {
+        FontStyle {
+            weight: weight,
+            width: width,
+            slant: slant,
+        }
+    }
+}
+impl Default for FontStyle {
+    fn default() -> FontStyle { FontStyle::NORMAL }
+}
+impl From<TypefaceStyle> for FontStyle {
+    fn from(s: TypefaceStyle) -> FontStyle {
+        match s {
+            TypefaceStyle::Normal => FontStyle::NORMAL,
+            TypefaceStyle::Bold => FontStyle::BOLD,
+            TypefaceStyle::Italic => FontStyle::ITALIC,
+            TypefaceStyle::BoldItalic => FontStyle::BOLD_ITALIC,
+        }
+    }
+}
+impl Into<sys::FontStyle> for FontStyle {
+    fn into(self) -> sys::FontStyle {
+        sys::FontStyle {
+            weight: self.weight as libc::c_int,
+            width: self.width as libc::c_int,
+            slant: match self.slant {
+                Slant::Upright => sys::FontSlant::Upright,
+                Slant::Italic => sys::FontSlant::Italic,
+                Slant::Oblique => sys::FontSlant::Oblique,
+            },
+        }
+    }
+}
+impl From<sys::FontStyle> for FontStyle {
+    fn from(f: sys::FontStyle) -> FontStyle {
+        let weight = if f.weight < 0 { 0 } else if f.weight > 1000 { 1000 } else { f.weight };
+        FontStyle {
+            weight: weight as u16,
+            width: FontWidth::from_ffi(f.width),
+            slant: match f.slant {
+                sys::FontSlant::Upright => Slant::Upright,
+                sys::FontSlant::Italic => Slant::Italic,
+                sys::FontSlant::Oblique => Slant::Oblique,
+            },
+        }
+    }
+}
+
+/// Skia takes paths as UTF-8, except on unix where any bytes go.
+#[cfg(unix)]
+fn path_bytes(p: &std::path::Path) -> Option<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(p.as_os_str().as_bytes())
+}
+#[cfg(not(unix))]
+fn path_bytes(p: &std::path::Path) -> Option<&[u8]> {
+    p.to_str().map(|s| s.as_bytes() )
+}
+
+/// A big endian SFNT table tag, eg `font_table_tag(b"cmap")`.
+pub type FontTableTag = u32;
+pub fn font_table_tag(tag: &[u8; 4]) -> FontTableTag {
+    (tag[0] as u32) << 24 | (tag[1] as u32) << 16 | (tag[2] as u32) << 8 | tag[3] as u32
+}
+
+/// A variable font's design axis, eg `wght` or `opsz`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VariationAxis {
+    pub tag: FontTableTag,
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+    /// Hidden axes shouldn't be shown in user interfaces.
+    pub hidden: bool,
+}
+
+/// A variable font instance named by its designer. Pass `coordinates` to
+/// `Typeface::clone_with_variations` to get the instance's typeface.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NamedInstance {
+    pub name: Option<String>,
+    pub coordinates: Vec<(FontTableTag, f32)>,
+}
+
 unsafe impl Send for Typeface { }
+/// Typefaces are equal if they are the same font, per `unique_id`.
+impl PartialEq for Typeface {
+    fn eq(&self, other: &Typeface) -> bool {
+        self.unique_id() == other.unique_id()
+    }
+}
+impl Eq for Typeface { }
+impl std::hash::Hash for Typeface {
+    fn hash<H>(&self, state: &mut H)
+        where H: std::hash::Hasher,
+    {
+        std::hash::Hash::hash(&self.unique_id(), state);
+    }
+}
 impl Clone for Typeface {
     fn clone(&self) -> Typeface {
         unsafe {
@@ -769,10 +2049,14 @@ impl Default for Typeface {
     }
 }
 impl Typeface {
-    pub fn new_from_name(name: &str, style: TypefaceStyle) -> Typeface {
+    /// `style` may be a `FontStyle` or a legacy `TypefaceStyle`.
+    pub fn new_from_name<S>(name: &str, style: S) -> Typeface
+        where S: Into<FontStyle>,
+    {
         Typeface(unsafe {
-            sys::sk_typeface_create_from_name(name.as_ptr() as *const _, name.len(),
-                                              style)
+            sys::sk_typeface_create_from_name_with_font_style(name.as_ptr() as *const _,
+                                                              name.len(),
+                                                              style.into().into())
         })
     }
     pub fn new_from_typeface(tf: Option<&Typeface>, style: TypefaceStyle) -> Typeface {
@@ -781,15 +2065,25 @@ impl Typeface {
             sys::sk_typeface_create_from_typeface(tf, style)
         })
     }
+    /// Load the first font in the file at `path`.
     pub fn new_from_path<T>(path: T) -> Option<Typeface>
         where T: AsRef<std::path::Path>,
     {
-        let p_str = path.as_ref().to_str();
-        if p_str.is_none() { return None; }
-        let p_str = p_str.unwrap();
+        Typeface::new_from_path_index(path, 0)
+    }
+    /// Load the `ttc_index`th font in the file at `path`. Anything but 0
+    /// only makes sense for font collections (`.ttc`/`.otc`).
+    pub fn new_from_path_index<T>(path: T, ttc_index: usize) -> Option<Typeface>
+        where T: AsRef<std::path::Path>,
+    {
+        let p_bytes = match path_bytes(path.as_ref()) {
+            Some(b) => b,
+            None => return None,
+        };
 
         let tf = unsafe {
-            sys::sk_typeface_create_from_path(p_str.as_ptr() as *const _, p_str.len())
+            sys::sk_typeface_create_from_path_index(p_bytes.as_ptr() as *const _, p_bytes.len(),
+                                                    ttc_index as libc::c_int)
         };
         if tf == ptr::null_mut() {
             None
@@ -797,4 +2091,189 @@ impl Typeface {
             Some(Typeface(tf))
         }
     }
+    /// Load the `ttc_index`th font in `data`, which is kept alive by the
+    /// typeface rather than copied, so eg an `Arc<[u8]>` can be shared between
+    /// several typefaces of a collection. TrueType, OpenType, collections, and,
+    /// if Skia's FreeType supports them, WOFF and WOFF2 are accepted.
+    pub fn from_data<T>(data: T, ttc_index: usize) -> Option<Typeface>
+        where T: AsRef<[u8]> + Send + 'static,
+    {
+        extern "C" fn release<T>(ctxt: *mut libc::c_void) {
+            unsafe {
+                drop(Box::from_raw(ctxt as *mut T));
+            }
+        }
+
+        let data = Box::new(data);
+        let (ptr, len) = {
+            let bytes = (*data).as_ref();
+            (bytes.as_ptr(), bytes.len())
+        };
+        let ctxt = Box::into_raw(data);
+        let tf = unsafe {
+            // Skia calls `release` even if it fails.
+            sys::sk_typeface_create_from_data(ptr as *const _, len, ttc_index as libc::c_int,
+                                              release::<T>, ctxt as *mut _)
+        };
+        if tf == ptr::null_mut() {
+            None
+        } else {
+            Some(Typeface(tf))
+        }
+    }
+
+    pub fn family_name(&self) -> String {
+        ffi_string(|s| unsafe {
+            sys::sk_typeface_get_family_name(self.0, s)
+        })
+    }
+    pub fn postscript_name(&self) -> Option<String> {
+        let mut found = false;
+        let name = ffi_string(|s| unsafe {
+            found = sys::sk_typeface_get_postscript_name(self.0, s);
+        });
+        if found { Some(name) } else { None }
+    }
+    /// Unique among all typefaces in this process, and stable for as long as
+    /// any reference to the typeface lives.
+    pub fn unique_id(&self) -> u32 {
+        unsafe {
+            sys::sk_typeface_unique_id(self.0)
+        }
+    }
+    pub fn count_glyphs(&self) -> usize {
+        unsafe {
+            sys::sk_typeface_count_glyphs(self.0) as usize
+        }
+    }
+    /// Returns 0 if the font has no units per em.
+    pub fn units_per_em(&self) -> u32 {
+        unsafe {
+            sys::sk_typeface_get_units_per_em(self.0) as u32
+        }
+    }
+    pub fn is_fixed_pitch(&self) -> bool {
+        unsafe {
+            sys::sk_typeface_is_fixed_pitch(self.0)
+        }
+    }
+    /// The union of all glyph bounds, for a text size of 1.
+    pub fn bounds(&self) -> FRect {
+        from_ffi_frect(unsafe {
+            sys::sk_typeface_get_bounds(self.0)
+        })
+    }
+    /// The tags of the font's SFNT tables.
+    pub fn table_tags(&self) -> Vec<FontTableTag> {
+        let count = unsafe {
+            sys::sk_typeface_count_tables(self.0)
+        };
+        if count <= 0 {
+            return vec![];
+        }
+        let mut tags: Vec<FontTableTag> = vec![0; count as usize];
+        let count = unsafe {
+            sys::sk_typeface_get_table_tags(self.0, tags.as_mut_ptr())
+        };
+        tags.truncate(count as usize);
+        tags
+    }
+    /// The raw contents of the table `tag`, or `None` if there is no such
+    /// table.
+    pub fn table_data(&self, tag: FontTableTag) -> Option<Vec<u8>> {
+        let len = unsafe {
+            sys::sk_typeface_get_table_size(self.0, tag)
+        };
+        if len == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; len];
+        let len = unsafe {
+            sys::sk_typeface_get_table_data(self.0, tag, 0, len, data.as_mut_ptr() as *mut _)
+        };
+        data.truncate(len);
+        Some(data)
+    }
+
+    /// The variation axes of a variable font. Empty for other fonts.
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        let count = unsafe {
+            sys::sk_typeface_get_variation_axes(self.0, ptr::null_mut(), 0)
+        };
+        if count <= 0 {
+            return vec![];
+        }
+        let mut axes: Vec<sys::VariationAxis> = vec![Default::default(); count as usize];
+        let count = unsafe {
+            sys::sk_typeface_get_variation_axes(self.0, axes.as_mut_ptr(), count)
+        };
+        axes.truncate(if count < 0 { 0 } else { count as usize });
+        axes.into_iter()
+            .map(|a| VariationAxis {
+                tag: a.tag,
+                min: a.min,
+                default: a.default,
+                max: a.max,
+                hidden: a.hidden,
+            })
+            .collect()
+    }
+    /// The current value of each axis of a variable font.
+    pub fn variation_position(&self) -> Vec<(FontTableTag, f32)> {
+        let count = unsafe {
+            sys::sk_typeface_get_variation_position(self.0, ptr::null_mut(), 0)
+        };
+        if count <= 0 {
+            return vec![];
+        }
+        let mut coords: Vec<sys::VariationCoordinate> = vec![Default::default(); count as usize];
+        let count = unsafe {
+            sys::sk_typeface_get_variation_position(self.0, coords.as_mut_ptr(), count)
+        };
+        coords.truncate(if count < 0 { 0 } else { count as usize });
+        coords.into_iter().map(|c| (c.axis, c.value) ).collect()
+    }
+    /// The instances predefined by the font's designer, eg "Bold Condensed".
+    /// Empty for fonts which aren't variable.
+    pub fn named_instances(&self) -> Vec<NamedInstance> {
+        let fvar = match self.table_data(font_table_tag(b"fvar")) {
+            Some(t) => t,
+            None => return vec![],
+        };
+        let name = self.table_data(font_table_tag(b"name"));
+        sfnt::named_instances(&fvar[..], name.as_ref().map(|n| &n[..] ))
+            .unwrap_or_default()
+    }
+    /// A new typeface with the axes in `coordinates` set. Axes which aren't
+    /// mentioned keep their current value; values are clamped to the axis'
+    /// range. Returns `None` if this isn't a variable font.
+    pub fn clone_with_variations(&self, coordinates: &[(FontTableTag, f32)]) -> Option<Typeface> {
+        let coords: Vec<sys::VariationCoordinate> = coordinates.iter()
+            .map(|&(axis, value)| sys::VariationCoordinate {
+                axis: axis,
+                value: value,
+            })
+            .collect();
+        let tf = unsafe {
+            sys::sk_typeface_clone_with_variations(self.0, coords.as_ptr(),
+                                                   coords.len() as libc::c_int)
+        };
+        if tf == ptr::null_mut() {
+            None
+        } else {
+            Some(Typeface(tf))
+        }
+    }
+
+    pub fn font_style(&self) -> FontStyle {
+        From::from(unsafe {
+            sys::sk_typeface_get_font_style(self.0)
+        })
+    }
+    pub fn is_bold(&self) -> bool {
+        self.font_style().weight >= 600
+    }
+    pub fn is_italic(&self) -> bool {
+        self.font_style().slant != Slant::Upright
+    }
 }
@@ -0,0 +1,112 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Distance based queries over a path's contours.
+
+use std::ptr;
+
+use sys;
+
+use super::{Path, FPoint, Matrix, from_ffi_point, from_ffi_matrix};
+
+pub use sys::MeasureMatrixFlags;
+
+/// Iterates over the contours of a path, measuring each. Zero length contours
+/// are skipped.
+pub struct PathMeasure(sys::ContourMeasureIter);
+impl Drop for PathMeasure {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_contour_measure_iter_delete(self.0)
+        }
+    }
+}
+impl PathMeasure {
+    /// If `force_closed` is true, open contours are measured as if they were
+    /// closed. `res_scale` controls the precision of the measurement; values
+    /// greater than 1 increase it, and should be used if the path is going to
+    /// be drawn scaled up.
+    pub fn new(path: &Path, force_closed: bool, res_scale: f32) -> PathMeasure {
+        PathMeasure(unsafe {
+            sys::sk_contour_measure_iter_new(path.0, force_closed, res_scale)
+        })
+    }
+}
+impl Iterator for PathMeasure {
+    type Item = ContourMeasure;
+    fn next(&mut self) -> Option<ContourMeasure> {
+        let cm = unsafe {
+            sys::sk_contour_measure_iter_next(self.0)
+        };
+        if cm == ptr::null_mut() {
+            None
+        } else {
+            Some(ContourMeasure(cm))
+        }
+    }
+}
+
+/// A single measured contour. Distances passed to the methods below are
+/// clamped to `[0, length]`.
+pub struct ContourMeasure(sys::ContourMeasure);
+impl Clone for ContourMeasure {
+    fn clone(&self) -> ContourMeasure {
+        unsafe {
+            sys::sk_contour_measure_ref(self.0);
+        }
+        ContourMeasure(self.0)
+    }
+}
+impl Drop for ContourMeasure {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_contour_measure_unref(self.0);
+        }
+    }
+}
+impl ContourMeasure {
+    pub fn length(&self) -> f32 {
+        unsafe {
+            sys::sk_contour_measure_length(self.0)
+        }
+    }
+    pub fn is_closed(&self) -> bool {
+        unsafe {
+            sys::sk_contour_measure_is_closed(self.0)
+        }
+    }
+    /// The position and unit tangent at `distance` along the contour.
+    pub fn pos_tan(&self, distance: f32) -> Option<(FPoint, FPoint)> {
+        let mut pos: sys::Point = Default::default();
+        let mut tan: sys::Point = Default::default();
+        let ok = unsafe {
+            sys::sk_contour_measure_get_pos_tan(self.0, distance, &mut pos as *mut _,
+                                                &mut tan as *mut _)
+        };
+        if ok {
+            Some((from_ffi_point(pos), from_ffi_point(tan)))
+        } else {
+            None
+        }
+    }
+    /// A matrix which translates to the position at `distance` and/or rotates
+    /// to align with the tangent there, depending on `flags`.
+    pub fn matrix_at(&self, distance: f32, flags: MeasureMatrixFlags) -> Option<Matrix> {
+        let mut m: sys::Matrix = Default::default();
+        let ok = unsafe {
+            sys::sk_contour_measure_get_matrix(self.0, distance, &mut m as *mut _, flags)
+        };
+        if ok { Some(from_ffi_matrix(m)) } else { None }
+    }
+    /// Append the part of the contour between `start` and `stop` to `dst`.
+    /// Returns false, leaving `dst` untouched, if the segment would be empty.
+    /// If `start_with_move` is false the segment continues `dst`'s last contour
+    /// with a line from its last point instead of starting a new one.
+    pub fn segment(&self, start: f32, stop: f32, dst: &mut Path,
+                   start_with_move: bool) -> bool {
+        unsafe {
+            sys::sk_contour_measure_get_segment(self.0, start, stop, dst.0, start_with_move)
+        }
+    }
+}
@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! `serde` support for the core value types. Types which wrap a Skia object
+//! are (de)serialized through a plain description struct.
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error as DeError;
+
+use euclid;
+
+use super::{ImageInfo, ColorType, AlphaType, ColorProfile, ColorSpace, Color, Paint, PaintStyle,
+            Align, StrokeCap, StrokeJoin, Path, PathFillType};
+
+// `skia-sys` doesn't know about serde, so its enums are mirrored here and
+// used through `#[serde(with = "...")]`.
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ColorType")]
+enum ColorTypeDef {
+    Unknown,
+    Alpha8,
+    RGB565,
+    ARGB4444,
+    RGBA8888,
+    BGRA8888,
+    Index8,
+    Gray8,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AlphaType")]
+enum AlphaTypeDef {
+    Unknown,
+    Opaque,
+    Premul,
+    Unpremul,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ColorProfile")]
+enum ColorProfileDef {
+    Linear,
+    SRGB,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "PaintStyle")]
+enum PaintStyleDef {
+    Fill,
+    Stroke,
+    StrokeAndFill,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Align")]
+enum AlignDef {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "StrokeCap")]
+enum StrokeCapDef {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "StrokeJoin")]
+enum StrokeJoinDef {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ImageInfoDesc {
+    width: i32,
+    height: i32,
+    #[serde(with = "ColorTypeDef")]
+    color_type: ColorType,
+    #[serde(with = "AlphaTypeDef")]
+    alpha_type: AlphaType,
+    #[serde(with = "ColorProfileDef")]
+    color_profile: ColorProfile,
+    /// `ColorSpace::serialize`d.
+    #[serde(default)]
+    color_space: Option<Vec<u8>>,
+}
+
+impl Serialize for ImageInfo {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        ImageInfoDesc {
+            width: self.size.width,
+            height: self.size.height,
+            color_type: self.color_type,
+            alpha_type: self.alpha_type,
+            color_profile: self.color_profile,
+            color_space: self.color_space.as_ref().map(|cs| cs.serialize() ),
+        }.serialize(s)
+    }
+}
+impl<'de> Deserialize<'de> for ImageInfo {
+    fn deserialize<D>(d: D) -> Result<ImageInfo, D::Error>
+        where D: Deserializer<'de>,
+    {
+        let desc = ImageInfoDesc::deserialize(d)?;
+        let color_space = match desc.color_space {
+            Some(data) => Some(ColorSpace::deserialize(&data)
+                               .ok_or_else(|| D::Error::custom("invalid color space") )?),
+            None => None,
+        };
+        Ok(ImageInfo {
+            size: euclid::Size2D::new(desc.width, desc.height),
+            color_type: desc.color_type,
+            alpha_type: desc.alpha_type,
+            color_profile: desc.color_profile,
+            color_space: color_space,
+        })
+    }
+}
+
+/// Colors are a single `0xAARRGGBB` integer.
+impl Serialize for Color {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        let argb = (self.a() as u32) << 24 | (self.r() as u32) << 16 |
+            (self.g() as u32) << 8 | self.b() as u32;
+        argb.serialize(s)
+    }
+}
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(d: D) -> Result<Color, D::Error>
+        where D: Deserializer<'de>,
+    {
+        let argb = u32::deserialize(d)?;
+        Ok(Color::new((argb >> 24) as u8, (argb >> 16) as u8,
+                      (argb >> 8) as u8, argb as u8))
+    }
+}
+
+/// Everything but the typeface, which isn't a value.
+#[derive(Serialize, Deserialize)]
+struct PaintDesc {
+    color: Color,
+    #[serde(with = "PaintStyleDef")]
+    style: PaintStyle,
+    anti_alias: bool,
+    stroke_width: f32,
+    stroke_miter: f32,
+    #[serde(with = "StrokeCapDef")]
+    stroke_cap: StrokeCap,
+    #[serde(with = "StrokeJoinDef")]
+    stroke_join: StrokeJoin,
+    text_size: f32,
+    text_x_scale: f32,
+    #[serde(with = "AlignDef")]
+    text_align: Align,
+    subpixel_text: bool,
+    lcd_render_text: bool,
+}
+
+impl Serialize for Paint {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        PaintDesc {
+            color: self.get_color(),
+            style: self.get_style(),
+            anti_alias: self.get_anti_alias(),
+            stroke_width: self.get_stroke_width(),
+            stroke_miter: self.get_stroke_miter(),
+            stroke_cap: self.get_stroke_cap(),
+            stroke_join: self.get_stroke_join(),
+            text_size: self.get_text_size(),
+            text_x_scale: self.get_text_x_scale(),
+            text_align: self.get_text_align(),
+            subpixel_text: self.get_subpixel_text(),
+            lcd_render_text: self.get_lcd_render_text(),
+        }.serialize(s)
+    }
+}
+impl<'de> Deserialize<'de> for Paint {
+    fn deserialize<D>(d: D) -> Result<Paint, D::Error>
+        where D: Deserializer<'de>,
+    {
+        let desc = PaintDesc::deserialize(d)?;
+        let mut paint: Paint = Default::default();
+        paint.set_color(desc.color);
+        paint.set_style(desc.style);
+        paint.set_anti_alias(desc.anti_alias);
+        paint.set_stroke_width(desc.stroke_width);
+        paint.set_stroke_miter(desc.stroke_miter);
+        paint.set_stroke_cap(desc.stroke_cap);
+        paint.set_stroke_join(desc.stroke_join);
+        paint.set_text_size(desc.text_size);
+        paint.set_text_x_scale(desc.text_x_scale);
+        paint.set_text_align(desc.text_align);
+        paint.set_subpixel_text(desc.subpixel_text);
+        paint.set_lcd_render_text(desc.lcd_render_text);
+        Ok(paint)
+    }
+}
+
+/// Paths are stored as absolute SVG path data, so conics come back as quads.
+#[derive(Serialize, Deserialize)]
+struct PathDesc {
+    fill_type: PathFillType,
+    d: String,
+}
+
+impl Serialize for Path {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        PathDesc {
+            fill_type: self.get_fill_type(),
+            d: self.to_svg(true),
+        }.serialize(s)
+    }
+}
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D>(d: D) -> Result<Path, D::Error>
+        where D: Deserializer<'de>,
+    {
+        let desc = PathDesc::deserialize(d)?;
+        let mut path = Path::from_svg(&desc.d)
+            .map_err(|_| D::Error::custom("invalid SVG path data") )?;
+        path.set_fill_type(desc.fill_type);
+        Ok(path)
+    }
+}
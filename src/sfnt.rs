@@ -0,0 +1,246 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Parsing of the SFNT tables Skia doesn't expose an API for.
+
+use super::{FontTableTag, NamedInstance};
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset.checked_add(2)?)
+        .map(|b| (b[0] as u16) << 8 | b[1] as u16 )
+}
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset.checked_add(4)?)
+        .map(|b| (b[0] as u32) << 24 | (b[1] as u32) << 16 | (b[2] as u32) << 8 | b[3] as u32 )
+}
+/// `base + index * size`, or `None` on overflow. The table fields are
+/// untrusted, and `usize` may be 32 bits.
+fn offset_of(base: usize, index: usize, size: usize) -> Option<usize> {
+    index.checked_mul(size)?.checked_add(base)
+}
+/// A 16.16 fixed point number.
+fn read_fixed(data: &[u8], offset: usize) -> Option<f32> {
+    read_u32(data, offset).map(|v| v as i32 as f32 / 65536.0 )
+}
+
+/// Parse the named instances of an `fvar` table, naming them from the `name`
+/// table if possible. Returns `None` if `fvar` is malformed.
+pub fn named_instances(fvar: &[u8], name: Option<&[u8]>) -> Option<Vec<NamedInstance>> {
+    let axes_offset = read_u16(fvar, 4)? as usize;
+    let axis_count = read_u16(fvar, 8)? as usize;
+    let axis_size = read_u16(fvar, 10)? as usize;
+    let instance_count = read_u16(fvar, 12)? as usize;
+    let instance_size = read_u16(fvar, 14)? as usize;
+    if instance_size < offset_of(4, axis_count, 4)? {
+        return None;
+    }
+
+    let mut tags = Vec::with_capacity(axis_count);
+    for i in 0..axis_count {
+        tags.push(read_u32(fvar, offset_of(axes_offset, i, axis_size)?)?);
+    }
+
+    let instances_offset = offset_of(axes_offset, axis_count, axis_size)?;
+    let mut instances = Vec::with_capacity(instance_count);
+    for i in 0..instance_count {
+        let offset = offset_of(instances_offset, i, instance_size)?;
+        let name_id = read_u16(fvar, offset)?;
+        let mut coordinates: Vec<(FontTableTag, f32)> = Vec::with_capacity(axis_count);
+        for (a, &tag) in tags.iter().enumerate() {
+            coordinates.push((tag, read_fixed(fvar, offset_of(offset.checked_add(4)?, a, 4)?)?));
+        }
+        instances.push(NamedInstance {
+            name: name.and_then(|n| lookup_name(n, name_id) ),
+            coordinates: coordinates,
+        });
+    }
+    Some(instances)
+}
+
+/// Find `name_id` in a `name` table, preferring English Windows names.
+fn lookup_name(name: &[u8], name_id: u16) -> Option<String> {
+    let count = read_u16(name, 2)? as usize;
+    let storage = read_u16(name, 4)? as usize;
+
+    let mut fallback = None;
+    for i in 0..count {
+        let record = offset_of(6, i, 12)?;
+        if read_u16(name, record + 6)? != name_id {
+            continue;
+        }
+        let platform = read_u16(name, record)?;
+        let language = read_u16(name, record + 4)?;
+        let len = read_u16(name, record + 8)? as usize;
+        let start = storage.checked_add(read_u16(name, record + 10)? as usize)?;
+        let bytes = match start.checked_add(len).and_then(|end| name.get(start..end) ) {
+            Some(b) => b,
+            None => continue,
+        };
+        let s = match platform {
+            // Unicode and Windows names are UTF-16BE.
+            0 | 3 => {
+                let units: Vec<u16> = bytes.chunks(2)
+                    .filter(|c| c.len() == 2 )
+                    .map(|c| (c[0] as u16) << 8 | c[1] as u16 )
+                    .collect();
+                String::from_utf16_lossy(&units)
+            },
+            // Assume Mac Roman names are ASCII.
+            1 => String::from_utf8_lossy(bytes).into_owned(),
+            _ => continue,
+        };
+        if platform == 3 && language == 0x409 {
+            return Some(s);
+        }
+        if fallback.is_none() {
+            fallback = Some(s);
+        }
+    }
+    fallback
+}
+
+#[cfg(test)]
+mod tests {
+    use std::usize;
+
+    use super::{named_instances, lookup_name, offset_of, read_u16, read_u32};
+    use super::super::{FontTableTag, NamedInstance, font_table_tag};
+
+    fn push_u16(out: &mut Vec<u8>, v: u16) {
+        out.push((v >> 8) as u8);
+        out.push(v as u8);
+    }
+    fn push_u32(out: &mut Vec<u8>, v: u32) {
+        push_u16(out, (v >> 16) as u16);
+        push_u16(out, v as u16);
+    }
+
+    /// An `fvar` table with `wght` and `wdth` axes, and instances named by
+    /// `name_ids` at `wght` 100 * (i + 1), `wdth` 100.
+    fn fvar(name_ids: &[u16]) -> Vec<u8> {
+        let mut out = vec![];
+        push_u16(&mut out, 1);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, 16);
+        push_u16(&mut out, 2);
+        push_u16(&mut out, 2);
+        push_u16(&mut out, 20);
+        push_u16(&mut out, name_ids.len() as u16);
+        push_u16(&mut out, 12);
+        for tag in &[b"wght", b"wdth"] {
+            push_u32(&mut out, font_table_tag(tag));
+            push_u32(&mut out, 0);
+            push_u32(&mut out, 0);
+            push_u32(&mut out, 0);
+            push_u16(&mut out, 0);
+            push_u16(&mut out, 0);
+        }
+        for (i, &id) in name_ids.iter().enumerate() {
+            push_u16(&mut out, id);
+            push_u16(&mut out, 0);
+            push_u32(&mut out, (100 * (i as u32 + 1)) << 16);
+            push_u32(&mut out, 100 << 16);
+        }
+        out
+    }
+
+    /// A `name` table with `(platform, encoding, language, name_id, string)`
+    /// records. Unicode and Windows strings are stored as UTF-16BE.
+    fn name(records: &[(u16, u16, u16, u16, &str)]) -> Vec<u8> {
+        let mut storage = vec![];
+        let mut out = vec![];
+        push_u16(&mut out, 0);
+        push_u16(&mut out, records.len() as u16);
+        push_u16(&mut out, 6 + records.len() as u16 * 12);
+        for &(platform, encoding, language, name_id, s) in records {
+            let start = storage.len();
+            if platform == 1 {
+                storage.extend_from_slice(s.as_bytes());
+            } else {
+                for unit in s.encode_utf16() {
+                    push_u16(&mut storage, unit);
+                }
+            }
+            push_u16(&mut out, platform);
+            push_u16(&mut out, encoding);
+            push_u16(&mut out, language);
+            push_u16(&mut out, name_id);
+            push_u16(&mut out, (storage.len() - start) as u16);
+            push_u16(&mut out, start as u16);
+        }
+        out.extend(storage);
+        out
+    }
+
+    fn coordinates(wght: f32) -> Vec<(FontTableTag, f32)> {
+        vec![(font_table_tag(b"wght"), wght), (font_table_tag(b"wdth"), 100.0)]
+    }
+
+    #[test]
+    fn instances() {
+        let name = name(&[(3, 1, 0x409, 256, "Thin"), (3, 1, 0x409, 257, "Regular")]);
+        let instances = named_instances(&fvar(&[256, 257, 300]), Some(&name)).unwrap();
+        assert_eq!(instances, vec![
+            NamedInstance { name: Some("Thin".to_string()), coordinates: coordinates(100.0) },
+            NamedInstance { name: Some("Regular".to_string()), coordinates: coordinates(200.0) },
+            NamedInstance { name: None, coordinates: coordinates(300.0) },
+        ]);
+
+        let instances = named_instances(&fvar(&[256]), None).unwrap();
+        assert_eq!(instances, vec![NamedInstance { name: None, coordinates: coordinates(100.0) }]);
+        assert_eq!(named_instances(&fvar(&[]), None), Some(vec![]));
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(offset_of(8, 3, 4), Some(20));
+        assert_eq!(offset_of(usize::MAX, 1, 1), None);
+        assert_eq!(offset_of(0, usize::MAX, 2), None);
+        assert_eq!(read_u16(&[0; 4], usize::MAX), None);
+        assert_eq!(read_u32(&[0; 4], usize::MAX - 2), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let full = fvar(&[256, 257]);
+        for len in 0..full.len() {
+            assert_eq!(named_instances(&full[..len], None), None, "{}", len);
+        }
+
+        // An instance record too small for the axes.
+        let mut small = full.clone();
+        small[15] = 8;
+        assert_eq!(named_instances(&small, None), None);
+
+        // Offsets, counts and sizes far past the end of the table.
+        let mut huge = full.clone();
+        for b in &mut huge[4..16] {
+            *b = 0xff;
+        }
+        assert_eq!(named_instances(&huge, None), None);
+
+        let name = name(&[(3, 1, 0x409, 256, "Thin")]);
+        for len in 0..name.len() {
+            assert_eq!(lookup_name(&name[..len], 256), None, "{}", len);
+        }
+    }
+
+    #[test]
+    fn name_fallback() {
+        // Windows English wins wherever it is.
+        let table = name(&[(1, 0, 0, 256, "Mac"), (3, 1, 0x407, 256, "Deutsch"),
+                           (3, 1, 0x409, 256, "English")]);
+        assert_eq!(lookup_name(&table, 256), Some("English".to_string()));
+
+        // Otherwise the first supported record.
+        let table = name(&[(2, 0, 0, 256, "ISO"), (1, 0, 0, 256, "Mac"),
+                           (0, 3, 0, 256, "Unicode")]);
+        assert_eq!(lookup_name(&table, 256), Some("Mac".to_string()));
+        let table = name(&[(0, 3, 0, 256, "Unicode \u{e9}"), (1, 0, 0, 256, "Mac")]);
+        assert_eq!(lookup_name(&table, 256), Some("Unicode \u{e9}".to_string()));
+
+        assert_eq!(lookup_name(&table, 257), None);
+        assert_eq!(lookup_name(&name(&[(2, 0, 0, 256, "ISO")]), 256), None);
+    }
+}
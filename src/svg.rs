@@ -0,0 +1,530 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! SVG path data (the `d` attribute) parsing and serialization.
+
+use std::fmt::Write;
+
+use euclid::point::Point2D;
+
+use super::{Path, PathSegment, FPoint, ArcSize, PathDirection, Error};
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.s.len() {
+            match self.s[self.pos] {
+                b' ' | b'\t' | b'\n' | b'\r' | b'\x0C' => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+    fn skip_ws_comma(&mut self) {
+        self.skip_ws();
+        if self.pos < self.s.len() && self.s[self.pos] == b',' {
+            self.pos += 1;
+            self.skip_ws();
+        }
+    }
+    fn at_end(&self) -> bool { self.pos >= self.s.len() }
+
+    fn command(&mut self) -> Option<u8> {
+        if self.at_end() { return None; }
+        match self.s[self.pos] {
+            c @ b'M' | c @ b'm' | c @ b'L' | c @ b'l' | c @ b'H' | c @ b'h' |
+            c @ b'V' | c @ b'v' | c @ b'C' | c @ b'c' | c @ b'S' | c @ b's' |
+            c @ b'Q' | c @ b'q' | c @ b'T' | c @ b't' | c @ b'A' | c @ b'a' |
+            c @ b'Z' | c @ b'z' => {
+                self.pos += 1;
+                Some(c)
+            },
+            _ => None,
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.pos < self.s.len() && self.s[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+    fn number(&mut self) -> Result<f32, Error> {
+        self.skip_ws_comma();
+        let start = self.pos;
+        if self.pos < self.s.len() && (self.s[self.pos] == b'+' || self.s[self.pos] == b'-') {
+            self.pos += 1;
+        }
+        let mut mantissa = self.digits();
+        if self.pos < self.s.len() && self.s[self.pos] == b'.' {
+            self.pos += 1;
+            mantissa += self.digits();
+        }
+        if mantissa == 0 {
+            self.pos = start;
+            return Err(Error::SvgPath);
+        }
+        if self.pos < self.s.len() && (self.s[self.pos] == b'e' || self.s[self.pos] == b'E') {
+            let exp_start = self.pos;
+            self.pos += 1;
+            if self.pos < self.s.len() && (self.s[self.pos] == b'+' || self.s[self.pos] == b'-') {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                self.pos = exp_start;
+            }
+        }
+
+        let text = ::std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+        text.parse().map_err(|_| Error::SvgPath )
+    }
+    /// Arc flags are a single `0` or `1` and needn't be separated from what
+    /// follows them.
+    fn flag(&mut self) -> Result<bool, Error> {
+        self.skip_ws_comma();
+        if self.at_end() { return Err(Error::SvgPath); }
+        let f = match self.s[self.pos] {
+            b'0' => false,
+            b'1' => true,
+            _ => return Err(Error::SvgPath),
+        };
+        self.pos += 1;
+        Ok(f)
+    }
+    fn point(&mut self) -> Result<FPoint, Error> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Point2D::new(x, y))
+    }
+    /// Is there another argument set for the current command?
+    fn has_number(&mut self) -> bool {
+        self.skip_ws();
+        if self.at_end() { return false; }
+        matches!(self.s[self.pos], b'0'..=b'9' | b'+' | b'-' | b'.')
+    }
+}
+
+fn offset(base: FPoint, p: FPoint) -> FPoint {
+    Point2D::new(base.x + p.x, base.y + p.y)
+}
+fn reflect(about: FPoint, p: FPoint) -> FPoint {
+    Point2D::new(2.0 * about.x - p.x, 2.0 * about.y - p.y)
+}
+
+/// What the parser builds; lets it be tested without Skia. All points are
+/// absolute.
+trait PathSink {
+    fn move_to(&mut self, to: FPoint);
+    fn line_to(&mut self, to: FPoint);
+    fn quad_to(&mut self, c: FPoint, to: FPoint);
+    fn cubic_to(&mut self, c1: FPoint, c2: FPoint, to: FPoint);
+    fn arc_to(&mut self, radii: FPoint, rotation: f32, size: ArcSize, sweep: PathDirection,
+              to: FPoint);
+    fn close(&mut self);
+}
+impl PathSink for Path {
+    fn move_to(&mut self, to: FPoint) { Path::move_to(self, to, false); }
+    fn line_to(&mut self, to: FPoint) { Path::line_to(self, to, false); }
+    fn quad_to(&mut self, c: FPoint, to: FPoint) { Path::quad_to(self, c, to, false); }
+    fn cubic_to(&mut self, c1: FPoint, c2: FPoint, to: FPoint) {
+        Path::cubic_to(self, c1, c2, to, false);
+    }
+    fn arc_to(&mut self, radii: FPoint, rotation: f32, size: ArcSize, sweep: PathDirection,
+              to: FPoint) {
+        Path::elliptical_arc_to(self, radii, rotation, size, sweep, to, false);
+    }
+    fn close(&mut self) { Path::close(self); }
+}
+
+pub fn parse(d: &str) -> Result<Path, Error> {
+    let mut path: Path = Default::default();
+    parse_into(d, &mut path)?;
+    Ok(path)
+}
+
+fn parse_into<S: PathSink>(d: &str, path: &mut S) -> Result<(), Error> {
+    let mut p = Parser {
+        s: d.as_bytes(),
+        pos: 0,
+    };
+    let origin: FPoint = Point2D::new(0.0, 0.0);
+    let mut cur = origin;
+    let mut start = origin;
+    // The last control point of a cubic or quad, for S and T respectively.
+    let mut last_cubic_ctrl: Option<FPoint> = None;
+    let mut last_quad_ctrl: Option<FPoint> = None;
+    let mut prev: Option<u8> = None;
+
+    p.skip_ws();
+    while !p.at_end() {
+        let cmd = match p.command() {
+            Some(c) => c,
+            None => match prev {
+                Some(b'M') if p.has_number() => b'L',
+                Some(b'm') if p.has_number() => b'l',
+                Some(b'Z') | Some(b'z') | None => return Err(Error::SvgPath),
+                Some(c) if p.has_number() => c,
+                _ => return Err(Error::SvgPath),
+            },
+        };
+        if prev.is_none() && cmd != b'M' && cmd != b'm' {
+            return Err(Error::SvgPath);
+        }
+
+        let relative = (cmd as char).is_lowercase();
+        let base = if relative { cur } else { origin };
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+        match (cmd as char).to_ascii_uppercase() {
+            'M' => {
+                let to = offset(base, p.point()?);
+                path.move_to(to);
+                cur = to;
+                start = to;
+            },
+            'L' => {
+                let to = offset(base, p.point()?);
+                path.line_to(to);
+                cur = to;
+            },
+            'H' => {
+                let x = p.number()? + base.x;
+                let to = Point2D::new(x, cur.y);
+                path.line_to(to);
+                cur = to;
+            },
+            'V' => {
+                let y = p.number()? + base.y;
+                let to = Point2D::new(cur.x, y);
+                path.line_to(to);
+                cur = to;
+            },
+            'C' => {
+                let c1 = offset(base, p.point()?);
+                let c2 = offset(base, p.point()?);
+                let to = offset(base, p.point()?);
+                path.cubic_to(c1, c2, to);
+                cubic_ctrl = Some(c2);
+                cur = to;
+            },
+            'S' => {
+                let c1 = last_cubic_ctrl.map(|c| reflect(cur, c) ).unwrap_or(cur);
+                let c2 = offset(base, p.point()?);
+                let to = offset(base, p.point()?);
+                path.cubic_to(c1, c2, to);
+                cubic_ctrl = Some(c2);
+                cur = to;
+            },
+            'Q' => {
+                let c = offset(base, p.point()?);
+                let to = offset(base, p.point()?);
+                path.quad_to(c, to);
+                quad_ctrl = Some(c);
+                cur = to;
+            },
+            'T' => {
+                let c = last_quad_ctrl.map(|c| reflect(cur, c) ).unwrap_or(cur);
+                let to = offset(base, p.point()?);
+                path.quad_to(c, to);
+                quad_ctrl = Some(c);
+                cur = to;
+            },
+            'A' => {
+                let radii = p.point()?;
+                let rotation = p.number()?;
+                let large = p.flag()?;
+                let sweep = p.flag()?;
+                let to = offset(base, p.point()?);
+                let size = if large { ArcSize::Large } else { ArcSize::Small };
+                let dir = if sweep { PathDirection::CW } else { PathDirection::CCW };
+                path.arc_to(radii, rotation, size, dir, to);
+                cur = to;
+            },
+            'Z' => {
+                path.close();
+                cur = start;
+            },
+            _ => unreachable!(),
+        }
+        last_cubic_ctrl = cubic_ctrl;
+        last_quad_ctrl = quad_ctrl;
+        prev = Some(cmd);
+
+        // A comma may only separate argument sets of the same command.
+        p.skip_ws();
+        if !p.at_end() && p.s[p.pos] == b',' {
+            p.pos += 1;
+            if !p.has_number() {
+                return Err(Error::SvgPath);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Split a conic into quads which are within a quarter pixel of it, as Skia
+/// does when it needs to hand conics to something that doesn't support them.
+/// Returns the control and end point of each quad.
+fn conic_to_quads(p0: FPoint, p1: FPoint, p2: FPoint, w: f32) -> Vec<(FPoint, FPoint)> {
+    let a = w - 1.0;
+    let k = a / (4.0 * (2.0 + a));
+    let x = k * (p0.x - 2.0 * p1.x + p2.x);
+    let y = k * (p0.y - 2.0 * p1.y + p2.y);
+    let mut error = (x * x + y * y).sqrt();
+    let mut pow2 = 0;
+    while error > 0.25 && pow2 < 5 {
+        error *= 0.25;
+        pow2 += 1;
+    }
+
+    let mut quads = Vec::with_capacity(1 << pow2);
+    chop_conic(p0, p1, p2, w, pow2, &mut quads);
+    quads
+}
+fn chop_conic(p0: FPoint, p1: FPoint, p2: FPoint, w: f32, level: u32,
+              out: &mut Vec<(FPoint, FPoint)>) {
+    if level == 0 {
+        out.push((p1, p2));
+        return;
+    }
+    let scale = 1.0 / (1.0 + w);
+    let new_w = (0.5 + w * 0.5).sqrt();
+    let mid = Point2D::new((p0.x + 2.0 * w * p1.x + p2.x) * scale * 0.5,
+                           (p0.y + 2.0 * w * p1.y + p2.y) * scale * 0.5);
+    let left = Point2D::new((p0.x + w * p1.x) * scale, (p0.y + w * p1.y) * scale);
+    let right = Point2D::new((w * p1.x + p2.x) * scale, (w * p1.y + p2.y) * scale);
+    chop_conic(p0, left, mid, new_w, level - 1, out);
+    chop_conic(mid, right, p2, new_w, level - 1, out);
+}
+
+struct Writer {
+    out: String,
+    absolute: bool,
+    cur: FPoint,
+}
+impl Writer {
+    fn command(&mut self, cmd: char) {
+        if self.absolute {
+            self.out.push(cmd);
+        } else {
+            self.out.push(cmd.to_ascii_lowercase());
+        }
+    }
+    fn number(&mut self, v: f32) {
+        // Avoid writing `-0`.
+        let v = if v == 0.0 { 0.0 } else { v };
+        write!(self.out, "{}", v).unwrap();
+    }
+    fn point(&mut self, p: FPoint, first: bool) {
+        let (x, y) = if self.absolute { (p.x, p.y) }
+                     else { (p.x - self.cur.x, p.y - self.cur.y) };
+        if !first {
+            self.out.push(' ');
+        }
+        self.number(x);
+        self.out.push(' ');
+        self.number(y);
+    }
+    fn segment(&mut self, cmd: char, points: &[FPoint]) {
+        self.command(cmd);
+        for (i, &p) in points.iter().enumerate() {
+            self.point(p, i == 0);
+        }
+        self.cur = *points.last().unwrap();
+    }
+}
+
+pub fn serialize(path: &Path, absolute: bool) -> String {
+    serialize_segments(path.raw_iter(), absolute)
+}
+
+fn serialize_segments<I>(segments: I, absolute: bool) -> String
+    where I: IntoIterator<Item = PathSegment>,
+{
+    let origin: FPoint = Point2D::new(0.0, 0.0);
+    let mut w = Writer {
+        out: String::new(),
+        absolute: absolute,
+        cur: origin,
+    };
+    let mut start = origin;
+
+    for seg in segments {
+        match seg {
+            PathSegment::Move(p0) => {
+                w.segment('M', &[p0]);
+                start = p0;
+            },
+            PathSegment::Line(_, p1) => w.segment('L', &[p1]),
+            PathSegment::Quad(_, p1, p2) => w.segment('Q', &[p1, p2]),
+            PathSegment::Conic { points, weight } => {
+                for (c, to) in conic_to_quads(points[0], points[1], points[2], weight) {
+                    w.segment('Q', &[c, to]);
+                }
+            },
+            PathSegment::Cubic(_, p1, p2, p3) => w.segment('C', &[p1, p2, p3]),
+            PathSegment::Close => {
+                w.command('Z');
+                w.cur = start;
+            },
+        }
+    }
+
+    w.out
+}
+
+#[cfg(test)]
+mod tests {
+    use euclid::point::Point2D;
+
+    use super::{PathSink, parse_into, serialize_segments, conic_to_quads};
+    use super::super::{FPoint, PathSegment, ArcSize, PathDirection, Error};
+
+    #[derive(PartialEq, Debug)]
+    enum Op {
+        Move(FPoint),
+        Line(FPoint),
+        Quad(FPoint, FPoint),
+        Cubic(FPoint, FPoint, FPoint),
+        Arc(FPoint, f32, ArcSize, PathDirection, FPoint),
+        Close,
+    }
+    impl PathSink for Vec<Op> {
+        fn move_to(&mut self, to: FPoint) { self.push(Op::Move(to)); }
+        fn line_to(&mut self, to: FPoint) { self.push(Op::Line(to)); }
+        fn quad_to(&mut self, c: FPoint, to: FPoint) { self.push(Op::Quad(c, to)); }
+        fn cubic_to(&mut self, c1: FPoint, c2: FPoint, to: FPoint) {
+            self.push(Op::Cubic(c1, c2, to));
+        }
+        fn arc_to(&mut self, radii: FPoint, rotation: f32, size: ArcSize, sweep: PathDirection,
+                  to: FPoint) {
+            self.push(Op::Arc(radii, rotation, size, sweep, to));
+        }
+        fn close(&mut self) { self.push(Op::Close); }
+    }
+
+    fn pt(x: f32, y: f32) -> FPoint { Point2D::new(x, y) }
+    fn parse(d: &str) -> Result<Vec<Op>, Error> {
+        let mut ops = vec![];
+        parse_into(d, &mut ops).map(|_| ops )
+    }
+
+    #[test]
+    fn implicit_commands() {
+        assert_eq!(parse("M1 2 3 4,5 6").unwrap(),
+                   vec![Op::Move(pt(1.0, 2.0)), Op::Line(pt(3.0, 4.0)), Op::Line(pt(5.0, 6.0))]);
+        assert_eq!(parse("m1 1 2 2").unwrap(),
+                   vec![Op::Move(pt(1.0, 1.0)), Op::Line(pt(3.0, 3.0))]);
+        assert_eq!(parse("M0 0L1 1 2 2").unwrap(),
+                   vec![Op::Move(pt(0.0, 0.0)), Op::Line(pt(1.0, 1.0)), Op::Line(pt(2.0, 2.0))]);
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse("M.5.5").unwrap(), vec![Op::Move(pt(0.5, 0.5))]);
+        assert_eq!(parse("M1e2-1E-1").unwrap(), vec![Op::Move(pt(100.0, -0.1))]);
+        assert_eq!(parse("M+1-2").unwrap(), vec![Op::Move(pt(1.0, -2.0))]);
+    }
+
+    #[test]
+    fn packed_arc_flags() {
+        assert_eq!(parse("M0 0A10 10 0 1016 16").unwrap(),
+                   vec![Op::Move(pt(0.0, 0.0)),
+                        Op::Arc(pt(10.0, 10.0), 0.0, ArcSize::Large, PathDirection::CCW,
+                                pt(16.0, 16.0))]);
+        assert_eq!(parse("M1 1a2 3 45 0 1 4 5").unwrap(),
+                   vec![Op::Move(pt(1.0, 1.0)),
+                        Op::Arc(pt(2.0, 3.0), 45.0, ArcSize::Small, PathDirection::CW,
+                                pt(5.0, 6.0))]);
+        assert!(parse("M0 0A10 10 0 2 0 1 1").is_err());
+    }
+
+    #[test]
+    fn horizontal_vertical_and_close() {
+        assert_eq!(parse("M1 1h2v3H0V0z m1 1").unwrap(),
+                   vec![Op::Move(pt(1.0, 1.0)), Op::Line(pt(3.0, 1.0)), Op::Line(pt(3.0, 4.0)),
+                        Op::Line(pt(0.0, 4.0)), Op::Line(pt(0.0, 0.0)), Op::Close,
+                        Op::Move(pt(2.0, 2.0))]);
+    }
+
+    #[test]
+    fn smooth_reflection() {
+        assert_eq!(parse("M0 0C1 1 2 2 3 3S6 6 7 7").unwrap(),
+                   vec![Op::Move(pt(0.0, 0.0)),
+                        Op::Cubic(pt(1.0, 1.0), pt(2.0, 2.0), pt(3.0, 3.0)),
+                        Op::Cubic(pt(4.0, 4.0), pt(6.0, 6.0), pt(7.0, 7.0))]);
+        assert_eq!(parse("M0 0Q1 1 2 0t2 0").unwrap(),
+                   vec![Op::Move(pt(0.0, 0.0)), Op::Quad(pt(1.0, 1.0), pt(2.0, 0.0)),
+                        Op::Quad(pt(3.0, -1.0), pt(4.0, 0.0))]);
+        // Without a preceding curve the control point is the current point.
+        assert_eq!(parse("M1 1S2 2 3 3T4 4").unwrap(),
+                   vec![Op::Move(pt(1.0, 1.0)),
+                        Op::Cubic(pt(1.0, 1.0), pt(2.0, 2.0), pt(3.0, 3.0)),
+                        Op::Quad(pt(3.0, 3.0), pt(4.0, 4.0))]);
+    }
+
+    #[test]
+    fn errors() {
+        for d in &["L1 2", "1 2", "M1 2 3", "M0 0,", "M0 0,,1 1", "M0 0Z 1 1", "M0 0Z,",
+                   ",M0 0", "M0 0X", "M0 0L", "M1e 1"] {
+            assert_eq!(parse(d), Err(Error::SvgPath), "{}", d);
+        }
+        assert_eq!(parse("").unwrap(), vec![]);
+        assert_eq!(parse("  M0 0  ").unwrap(), vec![Op::Move(pt(0.0, 0.0))]);
+    }
+
+    #[test]
+    fn serialize() {
+        let segs = vec![
+            PathSegment::Move(pt(10.0, 20.0)),
+            PathSegment::Line(pt(10.0, 20.0), pt(30.0, -0.0)),
+            PathSegment::Quad(pt(30.0, 0.0), pt(1.0, 2.0), pt(3.0, 4.0)),
+            PathSegment::Cubic(pt(3.0, 4.0), pt(5.0, 6.0), pt(7.0, 8.0), pt(9.5, 10.0)),
+            PathSegment::Close,
+            PathSegment::Move(pt(11.0, 21.0)),
+        ];
+        assert_eq!(serialize_segments(segs.clone(), true),
+                   "M10 20L30 0Q1 2 3 4C5 6 7 8 9.5 10ZM11 21");
+        assert_eq!(serialize_segments(segs, false),
+                   "m10 20l20 -20q-29 2 -27 4c2 2 4 4 6.5 6zm1 1");
+    }
+
+    #[test]
+    fn round_trip() {
+        let segs = vec![
+            PathSegment::Move(pt(1.0, 2.0)),
+            PathSegment::Line(pt(1.0, 2.0), pt(3.0, 4.0)),
+            PathSegment::Cubic(pt(3.0, 4.0), pt(5.0, 6.0), pt(7.0, 8.0), pt(9.0, 10.0)),
+            PathSegment::Close,
+        ];
+        let expected = vec![Op::Move(pt(1.0, 2.0)), Op::Line(pt(3.0, 4.0)),
+                            Op::Cubic(pt(5.0, 6.0), pt(7.0, 8.0), pt(9.0, 10.0)), Op::Close];
+        for &absolute in &[true, false] {
+            let d = serialize_segments(segs.clone(), absolute);
+            assert_eq!(parse(&d).unwrap(), expected, "{}", d);
+        }
+    }
+
+    #[test]
+    fn conics() {
+        // A conic with a weight of 1 is a quad.
+        let quads = conic_to_quads(pt(0.0, 0.0), pt(50.0, 100.0), pt(100.0, 0.0), 1.0);
+        assert_eq!(quads, vec![(pt(50.0, 100.0), pt(100.0, 0.0))]);
+
+        // A quarter circle needs subdividing, and must end where the conic does.
+        let w = 0.5f32.sqrt();
+        let quads = conic_to_quads(pt(100.0, 0.0), pt(100.0, 100.0), pt(0.0, 100.0), w);
+        assert!(quads.len() > 1);
+        assert_eq!(quads.last().unwrap().1, pt(0.0, 100.0));
+        for &(_, to) in &quads {
+            let r = (to.x * to.x + to.y * to.y).sqrt();
+            assert!((r - 100.0).abs() < 0.25, "{:?}", to);
+        }
+    }
+}
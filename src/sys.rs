@@ -0,0 +1,578 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Everything `skia-sys` declares, plus the parts of Skia's C API it doesn't
+//! declare yet. The latter must be provided by the linked Skia build.
+
+use libc::{c_char, c_int, c_void, size_t};
+
+pub use skia_sys::*;
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PathDirection {
+    CW,
+    CCW,
+}
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ArcSize {
+    Small,
+    Large,
+}
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AddPathMode {
+    Append,
+    Extend,
+}
+
+/// Row major, like `SkMatrix`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Matrix {
+    pub scale_x: f32,
+    pub skew_x: f32,
+    pub trans_x: f32,
+    pub skew_y: f32,
+    pub scale_y: f32,
+    pub trans_y: f32,
+    pub persp_0: f32,
+    pub persp_1: f32,
+    pub persp_2: f32,
+}
+/// `radii` are the x and y radii of the upper left, upper right, lower right
+/// and lower left corners.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct RRect {
+    pub rect: Rect,
+    pub radii: [Point; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PathVerb {
+    Move,
+    Line,
+    Quad,
+    Conic,
+    Cubic,
+    Close,
+    Done,
+}
+
+pub enum PathIterRef {}
+pub type PathIter = *mut PathIterRef;
+pub enum PathRawIterRef {}
+pub type PathRawIter = *mut PathRawIterRef;
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PathOp {
+    Difference,
+    Intersect,
+    Union,
+    XOR,
+    ReverseDifference,
+}
+
+pub enum OpBuilderRef {}
+pub type OpBuilder = *mut OpBuilderRef;
+
+/// Which parts of the matrix `sk_contour_measure_get_matrix` computes.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MeasureMatrixFlags {
+    GetPosition = 1,
+    GetTangent = 2,
+    GetPosAndTan = 3,
+}
+
+pub enum ContourMeasureIterRef {}
+pub type ContourMeasureIter = *mut ContourMeasureIterRef;
+/// Reference counted.
+pub enum ContourMeasureRef {}
+pub type ContourMeasure = *mut ContourMeasureRef;
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum StrokeCap {
+    Butt,
+    Round,
+    Square,
+}
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum StrokeJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Unpremultiplied.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Color4f {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+/// Reference counted.
+pub enum ColorSpaceRef {}
+pub type ColorSpace = *mut ColorSpaceRef;
+
+/// `SkColorSpace`'s parametric transfer function.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct TransferFunction {
+    pub g: f32,
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct IRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+/// Borrows `color_space`, which may be null, and the pixels at `addr`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Pixmap {
+    pub info: ImageInfo,
+    pub color_space: ColorSpace,
+    pub addr: *mut c_void,
+    pub row_bytes: size_t,
+}
+
+pub enum FontMgrRef {}
+pub type FontMgr = *mut FontMgrRef;
+pub enum FontStyleSetRef {}
+pub type FontStyleSet = *mut FontStyleSetRef;
+/// Aka `SkString`.
+pub enum StringRef {}
+pub type String = *mut StringRef;
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontSlant {
+    Upright,
+    Italic,
+    Oblique,
+}
+/// Aka `SkFontStyle`. `weight` is 0 to 1000, `width` 1 to 9.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FontStyle {
+    pub weight: c_int,
+    pub width: c_int,
+    pub slant: FontSlant,
+}
+
+/// Aka `SkFontParameters::Variation::Axis`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct VariationAxis {
+    pub tag: u32,
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+    pub hidden: bool,
+}
+/// Aka `SkFontArguments::VariationPosition::Coordinate`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct VariationCoordinate {
+    pub axis: u32,
+    pub value: f32,
+}
+
+pub enum FontRef {}
+pub type Font = *mut FontRef;
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontEdging {
+    Alias,
+    AntiAlias,
+    SubpixelAntiAlias,
+}
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontHinting {
+    None,
+    Slight,
+    Normal,
+    Full,
+}
+
+/// Bits of `FontMetrics::flags`.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FontMetricsFlags {
+    UnderlineThicknessIsValid = 1 << 0,
+    UnderlinePositionIsValid = 1 << 1,
+    StrikeoutThicknessIsValid = 1 << 2,
+    StrikeoutPositionIsValid = 1 << 3,
+    BoundsInvalid = 1 << 4,
+}
+/// Aka `SkFontMetrics`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct FontMetrics {
+    pub flags: u32,
+    pub top: f32,
+    pub ascent: f32,
+    pub descent: f32,
+    pub bottom: f32,
+    pub leading: f32,
+    pub avg_char_width: f32,
+    pub max_char_width: f32,
+    pub x_min: f32,
+    pub x_max: f32,
+    pub x_height: f32,
+    pub cap_height: f32,
+    pub underline_thickness: f32,
+    pub underline_position: f32,
+    pub strikeout_thickness: f32,
+    pub strikeout_position: f32,
+}
+
+pub enum TextBlobRef {}
+pub type TextBlob = *mut TextBlobRef;
+pub enum TextBlobBuilderRef {}
+pub type TextBlobBuilder = *mut TextBlobBuilderRef;
+/// Aka `SkTextBlobBuilder::RunBuffer`. Owned by the builder; the caller fills
+/// in the glyphs, and the positions if the run has any.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TextBlobRunBuffer {
+    pub glyphs: *mut u16,
+    pub pos: *mut f32,
+    pub utf8_text: *mut c_char,
+    pub clusters: *mut u32,
+}
+
+/// The `SkPath` a `Path` points to, for paths Skia only lends out.
+pub enum PathRef {}
+
+extern "C" {
+    /// `key` and `data` are copied.
+    pub fn sk_draw_annotation(surface: Surface, rect: Rect, key: *const c_char, key_len: size_t,
+                              data: *const c_void, data_len: size_t);
+
+    pub fn sk_path_conic_to(path: Path, p0: Point, p1: Point, weight: f32, relative: bool);
+    pub fn sk_path_arc_to(path: Path, oval: Rect, start_angle: f32, sweep_angle: f32,
+                          force_move_to: bool);
+    pub fn sk_path_arc_to_tangent(path: Path, p1: Point, p2: Point, radius: f32);
+    pub fn sk_path_elliptical_arc_to(path: Path, radii: Point, x_axis_rotate: f32, size: ArcSize,
+                                     sweep: PathDirection, to: Point, relative: bool);
+    pub fn sk_path_add_rect(path: Path, rect: Rect, dir: PathDirection);
+    pub fn sk_path_add_oval(path: Path, oval: Rect, dir: PathDirection);
+    pub fn sk_path_add_circle(path: Path, center: Point, radius: f32, dir: PathDirection);
+    pub fn sk_path_add_arc(path: Path, oval: Rect, start_angle: f32, sweep_angle: f32);
+    pub fn sk_path_add_rrect(path: Path, rrect: RRect, dir: PathDirection);
+    pub fn sk_path_add_poly(path: Path, points: *const Point, count: c_int, close: bool);
+    /// `matrix` may be null.
+    pub fn sk_path_add_path(path: Path, src: Path, matrix: *const Matrix, mode: AddPathMode);
+
+    /// The iterator borrows `path`.
+    pub fn sk_path_iter_new(path: Path, force_close: bool) -> PathIter;
+    pub fn sk_path_iter_delete(iter: PathIter);
+    /// Writes up to four points to `pts`, depending on the verb.
+    pub fn sk_path_iter_next(iter: PathIter, pts: *mut Point) -> PathVerb;
+    /// The weight of the last conic returned by `sk_path_iter_next`.
+    pub fn sk_path_iter_conic_weight(iter: PathIter) -> f32;
+    pub fn sk_path_raw_iter_new(path: Path) -> PathRawIter;
+    pub fn sk_path_raw_iter_delete(iter: PathRawIter);
+    pub fn sk_path_raw_iter_next(iter: PathRawIter, pts: *mut Point) -> PathVerb;
+    pub fn sk_path_raw_iter_conic_weight(iter: PathRawIter) -> f32;
+
+    /// The results are written to `result`. These return false, leaving it
+    /// untouched, if Skia fails.
+    pub fn sk_path_op(one: Path, two: Path, op: PathOp, result: Path) -> bool;
+    pub fn sk_path_simplify(path: Path, result: Path) -> bool;
+    pub fn sk_path_as_winding(path: Path, result: Path) -> bool;
+    pub fn sk_new_op_builder() -> OpBuilder;
+    pub fn sk_del_op_builder(builder: OpBuilder);
+    /// `path` is copied.
+    pub fn sk_op_builder_add(builder: OpBuilder, path: Path, op: PathOp);
+    pub fn sk_op_builder_resolve(builder: OpBuilder, result: Path) -> bool;
+
+    /// `path` is copied.
+    pub fn sk_contour_measure_iter_new(path: Path, force_closed: bool,
+                                       res_scale: f32) -> ContourMeasureIter;
+    pub fn sk_contour_measure_iter_delete(iter: ContourMeasureIter);
+    /// Returns a new reference, or null after the last contour.
+    pub fn sk_contour_measure_iter_next(iter: ContourMeasureIter) -> ContourMeasure;
+    pub fn sk_contour_measure_ref(cm: ContourMeasure);
+    pub fn sk_contour_measure_unref(cm: ContourMeasure);
+    pub fn sk_contour_measure_length(cm: ContourMeasure) -> f32;
+    pub fn sk_contour_measure_is_closed(cm: ContourMeasure) -> bool;
+    pub fn sk_contour_measure_get_pos_tan(cm: ContourMeasure, distance: f32, pos: *mut Point,
+                                          tan: *mut Point) -> bool;
+    pub fn sk_contour_measure_get_matrix(cm: ContourMeasure, distance: f32, m: *mut Matrix,
+                                         flags: MeasureMatrixFlags) -> bool;
+    /// Appends to `dst`.
+    pub fn sk_contour_measure_get_segment(cm: ContourMeasure, start: f32, stop: f32, dst: Path,
+                                          start_with_move: bool) -> bool;
+
+    pub fn sk_path_get_last_point(path: Path, p: *mut Point) -> bool;
+    pub fn sk_path_get_bounds(path: Path) -> Rect;
+    pub fn sk_path_compute_tight_bounds(path: Path) -> Rect;
+    pub fn sk_path_contains(path: Path, x: f32, y: f32) -> bool;
+    pub fn sk_path_conservatively_contains_rect(path: Path, rect: Rect) -> bool;
+    pub fn sk_path_is_empty(path: Path) -> bool;
+    pub fn sk_path_is_finite(path: Path) -> bool;
+    pub fn sk_path_is_convex(path: Path) -> bool;
+    /// These write the shape only if they return true.
+    pub fn sk_path_is_rect(path: Path, rect: *mut Rect) -> bool;
+    pub fn sk_path_is_oval(path: Path, oval: *mut Rect) -> bool;
+    pub fn sk_path_is_rrect(path: Path, rrect: *mut RRect) -> bool;
+    /// `pts` must have room for two points.
+    pub fn sk_path_is_line(path: Path, pts: *mut Point) -> bool;
+
+    pub fn sk_path_reverse_add_path(path: Path, src: Path);
+    pub fn sk_path_transform(path: Path, matrix: Matrix);
+    pub fn sk_path_offset(path: Path, dx: f32, dy: f32);
+    pub fn sk_path_is_interpolatable(path: Path, other: Path) -> bool;
+    /// Writes to `out`.
+    pub fn sk_path_interpolate(path: Path, other: Path, weight: f32, out: Path) -> bool;
+    pub fn sk_path_set_is_volatile(path: Path, volatile: bool);
+
+    pub fn sk_path_get_generation_id(path: Path) -> u32;
+    /// Returns the serialized size, and writes the data if `buffer` isn't
+    /// null.
+    pub fn sk_path_write_to_memory(path: Path, buffer: *mut c_void) -> size_t;
+    /// Returns the number of bytes read, or 0 if `data` is invalid.
+    pub fn sk_path_read_from_memory(path: Path, data: *const c_void, len: size_t) -> size_t;
+
+    pub fn sk_paint_get_stroke_width(paint: Paint) -> f32;
+    pub fn sk_paint_set_stroke_width(paint: Paint, width: f32);
+    pub fn sk_paint_get_stroke_miter(paint: Paint) -> f32;
+    pub fn sk_paint_set_stroke_miter(paint: Paint, miter: f32);
+    pub fn sk_paint_get_stroke_cap(paint: Paint) -> StrokeCap;
+    pub fn sk_paint_set_stroke_cap(paint: Paint, cap: StrokeCap);
+    pub fn sk_paint_get_stroke_join(paint: Paint) -> StrokeJoin;
+    pub fn sk_paint_set_stroke_join(paint: Paint, join: StrokeJoin);
+
+    pub fn sk_paint_get_color4f(paint: Paint) -> Color4f;
+    /// `color_space` is borrowed, and may be null for sRGB.
+    pub fn sk_paint_set_color4f(paint: Paint, color: Color4f, color_space: ColorSpace);
+    pub fn sk_color_space_ref(cs: ColorSpace);
+    pub fn sk_color_space_unref(cs: ColorSpace);
+    pub fn sk_color_space_equals(one: ColorSpace, two: ColorSpace) -> bool;
+    /// Returns a new reference.
+    pub fn sk_color_space_new_srgb() -> ColorSpace;
+
+    /// The color space constructors return a new reference, or null.
+    pub fn sk_color_space_new_srgb_linear() -> ColorSpace;
+    /// `gamut` is a row major 3x3 matrix to XYZ D50.
+    pub fn sk_color_space_new_rgb(transfer_fn: *const TransferFunction,
+                                  gamut: *const f32) -> ColorSpace;
+    pub fn sk_color_space_new_icc(data: *const c_void, len: size_t) -> ColorSpace;
+    pub fn sk_color_space_deserialize(data: *const c_void, len: size_t) -> ColorSpace;
+    pub fn sk_color_space_is_srgb(cs: ColorSpace) -> bool;
+    pub fn sk_color_space_gamma_is_linear(cs: ColorSpace) -> bool;
+    /// Returns the serialized size, and writes the data if `buffer` isn't
+    /// null.
+    pub fn sk_color_space_write_to_memory(cs: ColorSpace, buffer: *mut c_void) -> size_t;
+
+    // `ImageInfo` is `skia-sys`'s, which has no color space, so calls which
+    // need one take it separately. The color space arguments below are
+    // borrowed and may be null; the getters return a new reference, or null.
+    pub fn sk_new_raster_direct_surface_with_color_space(info: ImageInfo,
+                                                         color_space: ColorSpace,
+                                                         pixels: *mut c_void,
+                                                         row_bytes: size_t) -> Surface;
+    pub fn sk_new_render_target_surface_with_color_space(ctx: SkiaGrContextRef,
+                                                         budgeted: CacheManagement,
+                                                         info: ImageInfo,
+                                                         color_space: ColorSpace) -> Surface;
+    pub fn sk_surface_get_color_space(surface: Surface) -> ColorSpace;
+    pub fn sk_image_get_image_info(image: Image) -> ImageInfo;
+    pub fn sk_image_get_color_space(image: Image) -> ColorSpace;
+
+    /// 0 for `Unknown`.
+    pub fn sk_color_type_bytes_per_pixel(ct: ColorType) -> c_int;
+    /// The color spaces are borrowed, and may be null.
+    pub fn sk_convert_pixels(dst_info: ImageInfo, dst_color_space: ColorSpace, dst: *mut c_void,
+                             dst_row_bytes: size_t, src_info: ImageInfo,
+                             src_color_space: ColorSpace, src: *const c_void,
+                             src_row_bytes: size_t) -> bool;
+
+    /// Unpremultiplied.
+    pub fn sk_pixmap_get_color(pixmap: Pixmap, x: c_int, y: c_int) -> Color;
+    /// `area` may be null to erase everything.
+    pub fn sk_pixmap_erase(pixmap: Pixmap, color: Color, area: *const IRect) -> bool;
+    /// Copies the pixels. Returns null on failure.
+    pub fn sk_new_image_raster_copy(pixmap: Pixmap) -> Image;
+
+    pub fn sk_string_new() -> String;
+    pub fn sk_string_delete(s: String);
+    /// Not nul terminated; the length is written to `len`.
+    pub fn sk_string_data(s: String, len: *mut size_t) -> *const c_char;
+
+    pub fn sk_font_mgr_ref_default() -> FontMgr;
+    pub fn sk_font_mgr_ref(mgr: FontMgr);
+    pub fn sk_font_mgr_unref(mgr: FontMgr);
+    pub fn sk_font_mgr_count_families(mgr: FontMgr) -> c_int;
+    pub fn sk_font_mgr_get_family_name(mgr: FontMgr, index: c_int, name: String);
+    /// Never null; the set is empty if there is no such family.
+    pub fn sk_font_mgr_match_family(mgr: FontMgr, name: *const c_char,
+                                    name_len: size_t) -> FontStyleSet;
+    /// The typefaces returned below are null if nothing matches.
+    pub fn sk_font_mgr_match_family_style(mgr: FontMgr, name: *const c_char, name_len: size_t,
+                                          style: FontStyle) -> Typeface;
+    pub fn sk_font_mgr_match_family_style_character(mgr: FontMgr, name: *const c_char,
+                                                    name_len: size_t, style: FontStyle,
+                                                    bcp47: *const *const c_char,
+                                                    bcp47_lens: *const size_t,
+                                                    bcp47_count: c_int,
+                                                    character: i32) -> Typeface;
+    pub fn sk_font_style_set_unref(set: FontStyleSet);
+    pub fn sk_font_style_set_count(set: FontStyleSet) -> c_int;
+    pub fn sk_font_style_set_get_style(set: FontStyleSet, index: c_int,
+                                       style: *mut FontStyle, name: String);
+    pub fn sk_font_style_set_create_typeface(set: FontStyleSet, index: c_int) -> Typeface;
+    pub fn sk_font_style_set_match_style(set: FontStyleSet, style: FontStyle) -> Typeface;
+
+    /// `sk_typeface_create_from_name`, but taking a full `FontStyle` rather
+    /// than a `TypefaceStyle`.
+    pub fn sk_typeface_create_from_name_with_font_style(name: *const c_char, name_len: size_t,
+                                                        style: FontStyle) -> Typeface;
+    pub fn sk_typeface_get_font_style(tf: Typeface) -> FontStyle;
+
+    /// `sk_typeface_create_from_path`, but loading the `ttc_index`th font of a
+    /// collection.
+    pub fn sk_typeface_create_from_path_index(path: *const c_char, path_len: size_t,
+                                              ttc_index: c_int) -> Typeface;
+    /// `data` isn't copied; `release` is called with `ctxt` once Skia is done
+    /// with it, including when this fails and returns null.
+    pub fn sk_typeface_create_from_data(data: *const c_void, len: size_t, ttc_index: c_int,
+                                        release: extern "C" fn(*mut c_void),
+                                        ctxt: *mut c_void) -> Typeface;
+
+    pub fn sk_typeface_get_family_name(tf: Typeface, name: String);
+    /// Returns false, leaving `name` empty, if the font has no PostScript name.
+    pub fn sk_typeface_get_postscript_name(tf: Typeface, name: String) -> bool;
+    pub fn sk_typeface_unique_id(tf: Typeface) -> u32;
+    pub fn sk_typeface_count_glyphs(tf: Typeface) -> c_int;
+    pub fn sk_typeface_get_units_per_em(tf: Typeface) -> c_int;
+    pub fn sk_typeface_is_fixed_pitch(tf: Typeface) -> bool;
+    pub fn sk_typeface_get_bounds(tf: Typeface) -> Rect;
+    pub fn sk_typeface_count_tables(tf: Typeface) -> c_int;
+    /// `tags` must have room for `sk_typeface_count_tables` tags. Returns the
+    /// number written.
+    pub fn sk_typeface_get_table_tags(tf: Typeface, tags: *mut u32) -> c_int;
+    /// 0 if there is no such table.
+    pub fn sk_typeface_get_table_size(tf: Typeface, tag: u32) -> size_t;
+    /// Copies up to `len` bytes from `offset` into the table. Returns the
+    /// number copied.
+    pub fn sk_typeface_get_table_data(tf: Typeface, tag: u32, offset: size_t, len: size_t,
+                                      data: *mut c_void) -> size_t;
+
+    /// Both return the number of axes, writing at most `count` to the array,
+    /// which may be null if `count` is 0. -1 on failure.
+    pub fn sk_typeface_get_variation_axes(tf: Typeface, axes: *mut VariationAxis,
+                                          count: c_int) -> c_int;
+    pub fn sk_typeface_get_variation_position(tf: Typeface, coords: *mut VariationCoordinate,
+                                              count: c_int) -> c_int;
+    /// Null if `tf` isn't variable.
+    pub fn sk_typeface_clone_with_variations(tf: Typeface, coords: *const VariationCoordinate,
+                                             count: c_int) -> Typeface;
+
+    pub fn sk_new_font() -> Font;
+    pub fn sk_new_font_copy(font: Font) -> Font;
+    pub fn sk_del_font(font: Font);
+    /// Returns a new reference, or null for the default typeface.
+    pub fn sk_font_ref_typeface(font: Font) -> Typeface;
+    /// `tf` is borrowed, and may be null.
+    pub fn sk_font_set_typeface(font: Font, tf: Typeface);
+    pub fn sk_font_get_size(font: Font) -> f32;
+    pub fn sk_font_set_size(font: Font, size: f32);
+    pub fn sk_font_get_scale_x(font: Font) -> f32;
+    pub fn sk_font_set_scale_x(font: Font, scale: f32);
+    pub fn sk_font_get_skew_x(font: Font) -> f32;
+    pub fn sk_font_set_skew_x(font: Font, skew: f32);
+    pub fn sk_font_get_edging(font: Font) -> FontEdging;
+    pub fn sk_font_set_edging(font: Font, edging: FontEdging);
+    pub fn sk_font_get_hinting(font: Font) -> FontHinting;
+    pub fn sk_font_set_hinting(font: Font, hinting: FontHinting);
+    pub fn sk_font_get_embolden(font: Font) -> bool;
+    pub fn sk_font_set_embolden(font: Font, v: bool);
+    pub fn sk_font_get_baseline_snap(font: Font) -> bool;
+    pub fn sk_font_set_baseline_snap(font: Font, v: bool);
+    pub fn sk_font_get_linear_metrics(font: Font) -> bool;
+    pub fn sk_font_set_linear_metrics(font: Font, v: bool);
+    pub fn sk_font_get_embedded_bitmaps(font: Font) -> bool;
+    pub fn sk_font_set_embedded_bitmaps(font: Font, v: bool);
+    /// Returns the advance width. `bounds` and `paint` may be null.
+    pub fn sk_font_measure_text(font: Font, text: *const c_void, len: size_t,
+                                encoding: TextEncoding, bounds: *mut Rect, paint: Paint) -> f32;
+    pub fn sk_draw_simple_text(surface: Surface, text: *const c_void, len: size_t,
+                               encoding: TextEncoding, pos: Point, font: Font, paint: Paint);
+
+    /// Both return the recommended line spacing.
+    pub fn sk_font_get_metrics(font: Font, metrics: *mut FontMetrics) -> f32;
+    pub fn sk_paint_get_font_metrics(paint: Paint, metrics: *mut FontMetrics) -> f32;
+
+    /// Returns the number of glyphs `text` maps to, writing at most `count`
+    /// of them to `glyphs`, which may be null if `count` is 0.
+    pub fn sk_font_text_to_glyphs(font: Font, text: *const c_void, len: size_t,
+                                  encoding: TextEncoding, glyphs: *mut u16,
+                                  count: c_int) -> c_int;
+    /// `widths`, `bounds` and `paint` may be null.
+    pub fn sk_font_get_widths_bounds(font: Font, glyphs: *const u16, count: c_int,
+                                     widths: *mut f32, bounds: *mut Rect, paint: Paint);
+    pub fn sk_font_get_pos(font: Font, glyphs: *const u16, count: c_int, pos: *mut Point,
+                           origin: Point);
+
+    /// `glyphs` and `pos` each have `count` entries.
+    pub fn sk_draw_glyphs(surface: Surface, count: c_int, glyphs: *const u16, pos: *const Point,
+                          origin: Point, font: Font, paint: Paint);
+    pub fn sk_draw_text_blob(surface: Surface, blob: TextBlob, x: f32, y: f32, paint: Paint);
+
+    pub fn sk_text_blob_ref(blob: TextBlob);
+    pub fn sk_text_blob_unref(blob: TextBlob);
+    pub fn sk_text_blob_get_bounds(blob: TextBlob) -> Rect;
+    pub fn sk_text_blob_get_unique_id(blob: TextBlob) -> u32;
+
+    pub fn sk_new_text_blob_builder() -> TextBlobBuilder;
+    pub fn sk_del_text_blob_builder(builder: TextBlobBuilder);
+    /// The buffers returned below are valid until the next call on `builder`.
+    /// `bounds` may be null, in which case it is computed.
+    pub fn sk_text_blob_builder_alloc_run(builder: TextBlobBuilder, font: Font, count: c_int,
+                                          x: f32, y: f32,
+                                          bounds: *const Rect) -> TextBlobRunBuffer;
+    /// `pos` holds one x per glyph.
+    pub fn sk_text_blob_builder_alloc_run_pos_h(builder: TextBlobBuilder, font: Font,
+                                                count: c_int, y: f32,
+                                                bounds: *const Rect) -> TextBlobRunBuffer;
+    /// `pos` holds an x, y pair per glyph.
+    pub fn sk_text_blob_builder_alloc_run_pos(builder: TextBlobBuilder, font: Font,
+                                              count: c_int,
+                                              bounds: *const Rect) -> TextBlobRunBuffer;
+    /// `pos` holds an `SkRSXform`, four floats, per glyph.
+    pub fn sk_text_blob_builder_alloc_run_rsxform(builder: TextBlobBuilder, font: Font,
+                                                  count: c_int) -> TextBlobRunBuffer;
+    /// Null if no runs were allocated.
+    pub fn sk_text_blob_builder_make(builder: TextBlobBuilder) -> TextBlob;
+
+    /// Returns false, leaving `path` untouched, if `glyph` has no outline.
+    pub fn sk_font_get_path(font: Font, glyph: u16, path: Path) -> bool;
+    /// Calls `proc_` with each glyph's unscaled outline, or null, and the
+    /// matrix which scales it. Both are only valid during the call.
+    pub fn sk_font_get_paths(font: Font, glyphs: *const u16, count: c_int,
+                             proc_: extern "C" fn(*const PathRef, *const Matrix, *mut c_void),
+                             ctxt: *mut c_void);
+}
@@ -0,0 +1,191 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Immutable runs of positioned glyphs, for drawing shaped text.
+
+use std::ptr;
+use std::slice;
+
+use libc;
+use sys;
+
+use super::{FPoint, FRect, from_ffi_frect};
+use font::{Font, GlyphId};
+
+/// A rotation and uniform scale followed by a translation: a glyph's origin
+/// `(x, y)` maps to `(scos * x - ssin * y + tx, ssin * x + scos * y + ty)`.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct RSXform {
+    pub scos: f32,
+    pub ssin: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+impl RSXform {
+    pub fn new(scos: f32, ssin: f32, tx: f32, ty: f32) -> RSXform {
+        RSXform {
+            scos: scos,
+            ssin: ssin,
+            tx: tx,
+            ty: ty,
+        }
+    }
+    /// Scale by `scale` and rotate by `radians` about `anchor`, then
+    /// translate to `t`.
+    pub fn from_radians(scale: f32, radians: f32, t: FPoint, anchor: FPoint) -> RSXform {
+        let s = radians.sin() * scale;
+        let c = radians.cos() * scale;
+        RSXform::new(c, s, t.x + -c * anchor.x + s * anchor.y, t.y + -s * anchor.x - c * anchor.y)
+    }
+}
+
+/// Aka `SkTextBlob`.
+pub struct TextBlob(pub(crate) sys::TextBlob);
+unsafe impl Send for TextBlob { }
+unsafe impl Sync for TextBlob { }
+impl Clone for TextBlob {
+    fn clone(&self) -> TextBlob {
+        unsafe {
+            sys::sk_text_blob_ref(self.0);
+        }
+        TextBlob(self.0)
+    }
+}
+impl Drop for TextBlob {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_text_blob_unref(self.0);
+        }
+    }
+}
+impl TextBlob {
+    /// A conservative bounding box of all glyphs, relative to the blob's
+    /// origin.
+    pub fn bounds(&self) -> FRect {
+        from_ffi_frect(unsafe {
+            sys::sk_text_blob_get_bounds(self.0)
+        })
+    }
+    pub fn unique_id(&self) -> u32 {
+        unsafe {
+            sys::sk_text_blob_get_unique_id(self.0)
+        }
+    }
+}
+
+/// Aka `SkTextBlobBuilder`. Each run draws its glyphs with one `Font`.
+pub struct TextBlobBuilder(sys::TextBlobBuilder);
+impl Default for TextBlobBuilder {
+    fn default() -> TextBlobBuilder {
+        TextBlobBuilder(unsafe {
+            sys::sk_new_text_blob_builder()
+        })
+    }
+}
+impl Drop for TextBlobBuilder {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sk_del_text_blob_builder(self.0);
+        }
+    }
+}
+impl TextBlobBuilder {
+    fn copy_glyphs(buffer: &sys::TextBlobRunBuffer, glyphs: &[GlyphId]) {
+        unsafe {
+            slice::from_raw_parts_mut(buffer.glyphs, glyphs.len()).copy_from_slice(glyphs);
+        }
+    }
+
+    /// Glyphs laid out one after the other from `origin`, using their
+    /// advances.
+    pub fn add_run(&mut self, font: &Font, glyphs: &[GlyphId], origin: FPoint) -> &mut TextBlobBuilder {
+        // Skia doesn't allocate buffers for empty runs.
+        if glyphs.is_empty() {
+            return self;
+        }
+        let buffer = unsafe {
+            sys::sk_text_blob_builder_alloc_run(self.0, font.0, glyphs.len() as libc::c_int,
+                                                origin.x, origin.y, ptr::null())
+        };
+        TextBlobBuilder::copy_glyphs(&buffer, glyphs);
+        self
+    }
+    /// Glyphs on the baseline `y`, each with its own x position. Panics if
+    /// `xpos` isn't the same length as `glyphs`.
+    pub fn add_run_pos_h(&mut self, font: &Font, glyphs: &[GlyphId], xpos: &[f32],
+                         y: f32) -> &mut TextBlobBuilder {
+        assert_eq!(glyphs.len(), xpos.len());
+        if glyphs.is_empty() {
+            return self;
+        }
+        let buffer = unsafe {
+            sys::sk_text_blob_builder_alloc_run_pos_h(self.0, font.0, glyphs.len() as libc::c_int,
+                                                      y, ptr::null())
+        };
+        TextBlobBuilder::copy_glyphs(&buffer, glyphs);
+        unsafe {
+            slice::from_raw_parts_mut(buffer.pos, xpos.len()).copy_from_slice(xpos);
+        }
+        self
+    }
+    /// Glyphs each with their own position. Panics if `positions` isn't the
+    /// same length as `glyphs`.
+    pub fn add_run_pos(&mut self, font: &Font, glyphs: &[GlyphId],
+                       positions: &[FPoint]) -> &mut TextBlobBuilder {
+        assert_eq!(glyphs.len(), positions.len());
+        if glyphs.is_empty() {
+            return self;
+        }
+        let buffer = unsafe {
+            sys::sk_text_blob_builder_alloc_run_pos(self.0, font.0, glyphs.len() as libc::c_int,
+                                                    ptr::null())
+        };
+        TextBlobBuilder::copy_glyphs(&buffer, glyphs);
+        let pos = unsafe {
+            slice::from_raw_parts_mut(buffer.pos, positions.len() * 2)
+        };
+        for (dst, p) in pos.chunks_mut(2).zip(positions.iter()) {
+            dst[0] = p.x;
+            dst[1] = p.y;
+        }
+        self
+    }
+    /// Glyphs each with their own rotation, scale and position, eg for text
+    /// on a path. Panics if `xforms` isn't the same length as `glyphs`.
+    pub fn add_run_rsxform(&mut self, font: &Font, glyphs: &[GlyphId],
+                           xforms: &[RSXform]) -> &mut TextBlobBuilder {
+        assert_eq!(glyphs.len(), xforms.len());
+        if glyphs.is_empty() {
+            return self;
+        }
+        let buffer = unsafe {
+            sys::sk_text_blob_builder_alloc_run_rsxform(self.0, font.0,
+                                                        glyphs.len() as libc::c_int)
+        };
+        TextBlobBuilder::copy_glyphs(&buffer, glyphs);
+        let pos = unsafe {
+            slice::from_raw_parts_mut(buffer.pos, xforms.len() * 4)
+        };
+        for (dst, x) in pos.chunks_mut(4).zip(xforms.iter()) {
+            dst[0] = x.scos;
+            dst[1] = x.ssin;
+            dst[2] = x.tx;
+            dst[3] = x.ty;
+        }
+        self
+    }
+
+    /// Build a blob from the runs added so far, and reset the builder.
+    /// Returns `None` if no runs were added.
+    pub fn make(&mut self) -> Option<TextBlob> {
+        let blob = unsafe {
+            sys::sk_text_blob_builder_make(self.0)
+        };
+        if blob == ptr::null_mut() {
+            None
+        } else {
+            Some(TextBlob(blob))
+        }
+    }
+}